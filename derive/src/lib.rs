@@ -0,0 +1,323 @@
+//! Derive macros for [rofi-mode](https://docs.rs/rofi-mode).
+//!
+//! This crate should not be used directly;
+//! enable the `derive` feature of rofi-mode instead.
+
+/// Derive `rofi_mode::list::IntoEntry` for a struct,
+/// describing how it is displayed as an entry in a list mode.
+///
+/// The behaviour of the entry is controlled by `#[entry(...)]` attributes on the fields:
+///
+/// - `#[entry(text)]` marks the field whose [`Display`] output is the entry's text.
+///   Exactly one field must have it.
+///   Add `markup` (`#[entry(text, markup)]`) if the text contains Pango markup.
+/// - `#[entry(id)]` marks the field whose [`Display`] output is the entry's stable identifier,
+///   used for example to remember it in the selection history.
+/// - `#[entry(icon)]` marks the field containing the name or path of the entry's icon.
+///   It can be a string, a path, or an [`Option`] of either.
+/// - `#[entry(meta)]` marks a field whose [`Display`] output is used as hidden keywords
+///   when matching against the entry.
+///   It can be applied to any number of fields.
+/// - `#[entry(completion)]` marks the field whose [`Display`] output
+///   the input is set to when the entry is completed.
+/// - `#[entry(style = ...)]` on a [`bool`] field
+///   applies the given style to the entry when the field is `true`.
+///   The style can be any expression of type `rofi_mode::Style`,
+///   in which the names of `rofi_mode::Style`'s constants can be used on their own
+///   when combined with `|`, `&`, `^` and parentheses
+///   (such as `ACTIVE` or `URGENT | ACTIVE`).
+///   Any other name refers to whatever is in scope, such as a constant of your own.
+///
+/// `#[entry(style = ...)]` can also be applied to the struct itself,
+/// in which case the style is always applied.
+///
+/// # Examples
+///
+/// ```
+/// use rofi_mode::list::IntoEntry;
+///
+/// #[derive(rofi_mode::Entry)]
+/// struct Host {
+///     #[entry(text)]
+///     name: String,
+///     #[entry(meta)]
+///     address: String,
+///     #[entry(style = URGENT)]
+///     unreachable: bool,
+/// }
+///
+/// let entry = Host {
+///     name: "server".to_owned(),
+///     address: "192.0.2.1".to_owned(),
+///     unreachable: true,
+/// }
+/// .into_entry();
+///
+/// assert_eq!(entry.text.as_str(), "server");
+/// assert_eq!(entry.meta.as_str(), "192.0.2.1");
+/// assert_eq!(entry.style, rofi_mode::Style::URGENT);
+/// assert_eq!(entry.data.address, "192.0.2.1");
+/// ```
+///
+/// [`Display`]: std::fmt::Display
+#[proc_macro_derive(Entry, attributes(entry))]
+pub fn derive_entry(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    derive_entry_inner(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn derive_entry_inner(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            let message = "`Entry` can only be derived for structs";
+            return Err(syn::Error::new_spanned(data.enum_token, message));
+        }
+        Data::Union(data) => {
+            let message = "`Entry` can only be derived for structs";
+            return Err(syn::Error::new_spanned(data.union_token, message));
+        }
+    };
+
+    let mut styles = Vec::new();
+    for attr in entry_attrs(&input.attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("style") {
+                styles.push(style_expr(meta.value()?.parse()?));
+                Ok(())
+            } else {
+                Err(meta.error("unsupported struct attribute"))
+            }
+        })?;
+    }
+
+    let mut text = None;
+    let mut markup = false;
+    let mut id = None;
+    let mut icon = None;
+    let mut meta_fields = Vec::new();
+    let mut completion = None;
+    let mut field_styles = Vec::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let member = field
+            .ident
+            .clone()
+            .map_or_else(|| Member::Unnamed(i.into()), Member::Named);
+
+        for attr in entry_attrs(&field.attrs) {
+            let mut is_text = false;
+            let mut is_markup = false;
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("text") {
+                    if text.is_some() {
+                        return Err(meta.error("duplicate `text` field"));
+                    }
+                    text = Some(member.clone());
+                    is_text = true;
+                } else if meta.path.is_ident("markup") {
+                    is_markup = true;
+                } else if meta.path.is_ident("id") {
+                    if id.is_some() {
+                        return Err(meta.error("duplicate `id` field"));
+                    }
+                    id = Some(member.clone());
+                } else if meta.path.is_ident("icon") {
+                    if icon.is_some() {
+                        return Err(meta.error("duplicate `icon` field"));
+                    }
+                    icon = Some(member.clone());
+                } else if meta.path.is_ident("meta") {
+                    meta_fields.push(member.clone());
+                } else if meta.path.is_ident("completion") {
+                    if completion.is_some() {
+                        return Err(meta.error("duplicate `completion` field"));
+                    }
+                    completion = Some(member.clone());
+                } else if meta.path.is_ident("style") {
+                    field_styles.push((member.clone(), style_expr(meta.value()?.parse()?)));
+                } else {
+                    return Err(meta.error("unsupported field attribute"));
+                }
+                Ok(())
+            })?;
+            if is_markup {
+                if !is_text {
+                    let message = "`markup` can only be applied to the `text` field";
+                    return Err(syn::Error::new_spanned(attr, message));
+                }
+                markup = true;
+            }
+        }
+    }
+
+    let text = text.ok_or_else(|| {
+        let message = "deriving `Entry` requires a field marked `#[entry(text)]`";
+        syn::Error::new(Span::call_site(), message)
+    })?;
+
+    let icon = match icon {
+        Some(icon) => quote!(::rofi_mode::list::__private::Icon::icon(&self.#icon)),
+        None => quote!(::core::option::Option::None),
+    };
+    let id = match id {
+        Some(id) => quote!(::core::option::Option::Some(
+            ::rofi_mode::format!("{}", self.#id)
+        )),
+        None => quote!(::core::option::Option::None),
+    };
+    let completion = match completion {
+        Some(completion) => {
+            quote!(::core::option::Option::Some(
+                ::rofi_mode::format!("{}", self.#completion)
+            ))
+        }
+        None => quote!(::core::option::Option::None),
+    };
+    let field_styles = field_styles.iter().map(|(member, style)| {
+        quote! {
+            if self.#member {
+                style |= #style;
+            }
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::rofi_mode::list::IntoEntry for #name #ty_generics #where_clause {
+            fn into_entry(self) -> ::rofi_mode::list::Entry<Self> {
+                let text = ::rofi_mode::format!("{}", self.#text);
+                #[allow(unused_mut)]
+                let mut style = ::rofi_mode::Style::NORMAL #(| #styles)*;
+                #(#field_styles)*
+                #[allow(unused_mut)]
+                let mut meta = ::rofi_mode::String::new();
+                #(::rofi_mode::list::__private::push_meta(&mut meta, &self.#meta_fields);)*
+                let id = #id;
+                let icon = #icon;
+                let completion = #completion;
+                let mut entry = ::rofi_mode::list::Entry::new(text, self)
+                    .with_markup(#markup)
+                    .with_style(style)
+                    .with_meta(meta);
+                entry.id = id;
+                entry.icon = icon;
+                entry.completion = completion;
+                entry
+            }
+        }
+    })
+}
+
+fn entry_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("entry"))
+}
+
+/// The names of the constants of `rofi_mode::Style`.
+const STYLE_CONSTANTS: [&str; 7] = [
+    "NORMAL",
+    "URGENT",
+    "ACTIVE",
+    "SELECTED",
+    "MARKUP",
+    "ALT",
+    "HIGHLIGHT",
+];
+
+/// Resolve the names of `rofi_mode::Style` constants in a style expression.
+fn style_expr(expr: Expr) -> Expr {
+    match expr {
+        Expr::Path(path)
+            if path.qself.is_none()
+                && path.path.get_ident().map_or(false, |ident| {
+                    STYLE_CONSTANTS.iter().any(|name| ident == name)
+                }) =>
+        {
+            let ident = path.path.get_ident();
+            syn::parse_quote!(::rofi_mode::Style::#ident)
+        }
+        Expr::Binary(mut binary) => {
+            *binary.left = style_expr(*binary.left);
+            *binary.right = style_expr(*binary.right);
+            Expr::Binary(binary)
+        }
+        Expr::Paren(mut paren) => {
+            *paren.expr = style_expr(*paren.expr);
+            Expr::Paren(paren)
+        }
+        expr => expr,
+    }
+}
+
+use proc_macro2::Span;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Attribute;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Expr;
+use syn::Member;
+
+/// Uses of the derive macro that should fail to compile.
+///
+/// A name in a style that is not one of `rofi_mode::Style`'s constants
+/// must refer to something in scope:
+///
+/// ```compile_fail
+/// #[derive(rofi_mode::Entry)]
+/// struct Host {
+///     #[entry(text)]
+///     name: String,
+///     #[entry(style = UNREACHABLE)]
+///     unreachable: bool,
+/// }
+/// ```
+///
+/// Styles can only be applied by `bool` fields:
+///
+/// ```compile_fail
+/// #[derive(rofi_mode::Entry)]
+/// struct Host {
+///     #[entry(text)]
+///     name: String,
+///     #[entry(style = URGENT)]
+///     unreachable: u8,
+/// }
+/// ```
+///
+/// There must be a text field:
+///
+/// ```compile_fail
+/// #[derive(rofi_mode::Entry)]
+/// struct Host {
+///     #[entry(meta)]
+///     name: String,
+/// }
+/// ```
+///
+/// Only one field can be the text:
+///
+/// ```compile_fail
+/// #[derive(rofi_mode::Entry)]
+/// struct Host {
+///     #[entry(text)]
+///     name: String,
+///     #[entry(text)]
+///     address: String,
+/// }
+/// ```
+///
+/// Enums are not supported:
+///
+/// ```compile_fail
+/// #[derive(rofi_mode::Entry)]
+/// enum Host {
+///     Server,
+/// }
+/// ```
+#[cfg(doctest)]
+mod compile_fail {}
@@ -0,0 +1,78 @@
+//! Tests of the `Entry` derive macro that should compile.
+//! The tests that should fail to compile are doctests in the `compile_fail` module.
+
+const STALE: Style = Style::URGENT;
+
+#[derive(rofi_mode::Entry)]
+#[entry(style = MARKUP)]
+struct Package {
+    #[entry(text)]
+    name: String,
+    #[entry(id)]
+    id: u32,
+    #[entry(meta)]
+    version: String,
+    #[entry(meta)]
+    repository: &'static str,
+    #[entry(completion)]
+    install_name: String,
+    #[entry(style = ACTIVE | SELECTED)]
+    installed: bool,
+    #[entry(style = STALE)]
+    outdated: bool,
+    #[entry(style = Style::HIGHLIGHT)]
+    pinned: bool,
+}
+
+#[test]
+fn styles() {
+    let package = |installed, outdated| Package {
+        name: "rofi".to_owned(),
+        id: 7,
+        version: "1.7.5".to_owned(),
+        repository: "extra",
+        install_name: "rofi-wayland".to_owned(),
+        installed,
+        outdated,
+        pinned: true,
+    };
+
+    let entry = package(true, false).into_entry();
+    assert_eq!(entry.text.as_str(), "rofi");
+    assert_eq!(entry.id.as_ref().unwrap().as_str(), "7");
+    assert_eq!(entry.meta.as_str(), "1.7.5 extra");
+    assert_eq!(entry.completed().as_str(), "rofi-wayland");
+    assert_eq!(
+        entry.style,
+        Style::MARKUP | Style::ACTIVE | Style::SELECTED | Style::HIGHLIGHT,
+    );
+
+    let entry = package(false, true).into_entry();
+    assert_eq!(
+        entry.style,
+        Style::MARKUP | Style::URGENT | Style::HIGHLIGHT
+    );
+}
+
+#[derive(rofi_mode::Entry)]
+struct Icon {
+    #[entry(text, markup)]
+    label: &'static str,
+    #[entry(icon)]
+    icon: Option<&'static str>,
+}
+
+#[test]
+fn markup_and_icon() {
+    let entry = Icon {
+        label: "<b>bold</b>",
+        icon: Some("folder"),
+    }
+    .into_entry();
+    assert!(entry.markup);
+    assert_eq!(entry.plain_text().as_str(), "bold");
+    assert_eq!(entry.icon.as_ref().unwrap().as_str(), "folder");
+}
+
+use rofi_mode::list::IntoEntry;
+use rofi_mode::Style;
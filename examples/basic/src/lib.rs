@@ -78,10 +78,6 @@ impl<'rofi> rofi_mode::Mode<'rofi> for Mode<'rofi> {
         rofi_mode::Action::Reload
     }
 
-    fn matches(&self, line: usize, matcher: rofi_mode::Matcher<'_>) -> bool {
-        matcher.matches(&self.entries[line])
-    }
-
     fn message(&mut self) -> rofi_mode::String {
         let entries = self.entries.len();
         if entries == 1 {
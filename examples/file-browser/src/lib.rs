@@ -5,13 +5,27 @@ struct Mode<'rofi> {
     dir: PathBuf,
     entries: Vec<Entry>,
     home_dir: Option<PathBuf>,
+    state: State<Data>,
 }
 
+/// The state remembered between runs.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct Data {
+    last_dir: Option<PathBuf>,
+}
+
+impl rofi_mode::state::Schema for Data {}
+
 impl<'rofi> rofi_mode::Mode<'rofi> for Mode<'rofi> {
     const NAME: &'static str = "plugin-example-file-browser\0";
+    const ICON: Option<&'static str> = Some("folder");
 
     fn init(api: rofi_mode::Api<'rofi>) -> Result<Self, ()> {
-        let dir = env::current_dir().map_err(drop)?;
+        let state = State::<Data>::load(&api);
+        let dir = match &state.last_dir {
+            Some(dir) if dir.is_dir() => dir.clone(),
+            _ => env::current_dir().map_err(drop)?,
+        };
         // `home_dir` is only deprecated because of Windows behaviour; on Unix it’s fine
         #[allow(deprecated)]
         let home_dir = env::home_dir();
@@ -24,11 +38,16 @@ impl<'rofi> rofi_mode::Mode<'rofi> for Mode<'rofi> {
                 file_type: FileType::Dir,
             }],
             home_dir,
+            state,
         };
         this.update_entries();
         Ok(this)
     }
 
+    fn destroy(mut self, _action: Option<rofi_mode::Action>) {
+        self.state.last_dir = Some(self.dir);
+    }
+
     fn entries(&mut self) -> usize {
         self.entries.len()
     }
@@ -158,6 +177,7 @@ enum FileType {
     File,
 }
 
+use rofi_mode::state::State;
 use std::env;
 use std::ffi::CString;
 use std::ffi::OsString;
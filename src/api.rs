@@ -6,8 +6,8 @@
 /// to be only accessible while Rofi is running.
 #[derive(Debug)]
 pub struct Api<'rofi> {
-    display_name: ptr::NonNull<*mut u8>,
-    // Values are irrelevant when `*display_name == NULL`
+    mode: ptr::NonNull<ffi::Mode>,
+    // Values are irrelevant when `mode.display_name == NULL`
     display_name_len: usize,
     display_name_capacity: usize,
     lifetime: PhantomData<&'rofi ()>,
@@ -20,15 +20,110 @@ unsafe impl Send for Api<'_> {}
 unsafe impl Sync for Api<'_> {}
 
 impl Api<'_> {
-    pub(crate) unsafe fn new(display_name: ptr::NonNull<*mut u8>) -> Self {
+    pub(crate) unsafe fn new(mode: ptr::NonNull<ffi::Mode>) -> Self {
         Self {
-            display_name,
+            mode,
             display_name_len: 0,
             display_name_capacity: 0,
             lifetime: PhantomData,
         }
     }
 
+    /// Get the name of the current mode,
+    /// as it was exported to Rofi.
+    ///
+    /// This is usually [`Mode::NAME`] without its nul terminator,
+    /// but differs if the name was overridden in [`export_mode!`].
+    /// Invalid UTF-8 in the name is replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// [`Mode::NAME`]: crate::Mode::NAME
+    /// [`export_mode!`]: crate::export_mode
+    #[must_use]
+    pub fn mode_name(&self) -> Cow<'_, str> {
+        // SAFETY: Nobody mutates the name of the mode.
+        let name = unsafe { (*self.mode.as_ptr()).name };
+        unsafe { CStr::from_ptr(name) }.to_string_lossy()
+    }
+
+    /// Get the index of the active mode with the given name,
+    /// for use with [`Action::SetMode`].
+    ///
+    /// Modes are identified by the name they were loaded with,
+    /// such as `window` or `drun`,
+    /// and their indices depend on the order they were given to Rofi in.
+    /// Returns [`None`] if no active mode has the given name.
+    ///
+    /// [`Action::SetMode`]: crate::Action::SetMode
+    #[must_use]
+    pub fn mode_index(&self, name: &str) -> Option<u16> {
+        mode_index(name)
+    }
+
+    /// Get the modes Rofi has enabled in this session,
+    /// in the order of their indices.
+    #[must_use]
+    pub fn modes(&self) -> Vec<ModeInfo> {
+        unsafe { self.mode_infos(enabled_modes()) }
+    }
+
+    /// Get the mode currently shown by Rofi.
+    ///
+    /// Returns [`None`] if Rofi isn't showing anything,
+    /// or if the mode shown isn't one of [the enabled modes](Self::modes).
+    #[must_use]
+    pub fn current_mode(&self) -> Option<ModeInfo> {
+        let current = current_mode();
+        let (index, mode) = enabled_modes().find(|&(_, mode)| ptr::eq(mode, current))?;
+        Some(unsafe { self.mode_info(index, mode) })
+    }
+
+    /// Whether this mode is the one currently shown by Rofi.
+    ///
+    /// This is `false` when the mode is shown as part of a combi mode.
+    #[must_use]
+    pub fn is_current(&self) -> bool {
+        ptr::eq(current_mode(), self.mode.as_ptr())
+    }
+
+    /// Whether Rofi is currently showing its `combi` mode,
+    /// which lists the entries of several modes at once.
+    ///
+    /// Rofi doesn't expose which modes `combi` includes,
+    /// so this doesn't mean this mode is one of them.
+    #[must_use]
+    pub fn is_combi_current(&self) -> bool {
+        unsafe { is_combi(current_mode()) }
+    }
+
+    /// # Safety
+    ///
+    /// `modes` must yield modes loaded by Rofi.
+    unsafe fn mode_infos<I>(&self, modes: I) -> Vec<ModeInfo>
+    where
+        I: Iterator<Item = (c_uint, *const ffi::Mode)>,
+    {
+        modes
+            .map(|(index, mode)| unsafe { self.mode_info(index, mode) })
+            .collect()
+    }
+
+    /// # Safety
+    ///
+    /// `mode` must be a mode loaded by Rofi.
+    unsafe fn mode_info(&self, index: c_uint, mode: *const ffi::Mode) -> ModeInfo {
+        ModeInfo {
+            // Rofi supports at most 1000 modes.
+            index: index as u16,
+            name: unsafe { c_str((*mode).name) }.unwrap_or_default(),
+            display_name: unsafe { c_str((*mode).display_name) },
+            is_self: ptr::eq(mode, self.mode.as_ptr()),
+        }
+    }
+
+    fn display_name_ptr(&self) -> *mut *mut u8 {
+        unsafe { ptr::addr_of_mut!((*self.mode.as_ptr()).display_name) }.cast()
+    }
+
     /// Get the display name of the current mode (the text displayed before the colon).
     ///
     /// Returns [`None`] if there isn't one,
@@ -38,7 +133,7 @@ impl Api<'_> {
     #[must_use]
     pub fn display_name(&self) -> Option<&str> {
         // SAFETY: Rofi never mutates the display name, and we only mutate it with an `&mut Api`.
-        let ptr = *unsafe { self.display_name.as_ref() };
+        let ptr = unsafe { *self.display_name_ptr() };
 
         if ptr.is_null() {
             return None;
@@ -53,7 +148,7 @@ impl Api<'_> {
         // SAFETY: In order for functions on this type to be called, we must be inside one of
         // `Mode`'s methods. This means that Rofi guarantees us it won't be reading the display
         // name at this point in time.
-        let ptr = unsafe { self.display_name.as_mut() };
+        let ptr = unsafe { &mut *self.display_name_ptr() };
 
         let old_len = self.display_name_len;
         let old_capacity = self.display_name_capacity;
@@ -184,6 +279,166 @@ impl Api<'_> {
         }
         unsafe { cairo::Surface::from_raw_full(ptr) }.map_err(IconError::Surface)
     }
+
+    /// Request that the given line be selected
+    /// once Rofi has next reloaded the list of entries,
+    /// for example after returning [`Action::Reload`].
+    ///
+    /// The line is an index into the full list of entries,
+    /// like the indices Rofi passes to [`Mode::react`].
+    /// If it is filtered out by the user's input,
+    /// Rofi selects the first visible line instead.
+    /// Later requests replace earlier ones.
+    ///
+    /// [`Action::Reload`]: crate::Action::Reload
+    /// [`Mode::react`]: crate::Mode::react
+    pub fn select_line_after_reload(&mut self, line: usize) {
+        let line = line.try_into().unwrap_or(c_uint::MAX);
+        PENDING_SELECTION.with(|pending| pending.set(Some((self.mode.as_ptr(), line))));
+    }
+
+    /// Show a notification in the message bar
+    /// until the user triggers the next event.
+    ///
+    /// The message is plain text, not markup.
+    /// See [the `notification` module](crate::notification) for more.
+    pub fn notify<M: Into<String>>(&mut self, message: M, severity: Severity) {
+        self.notify_with(Notification::new(message, severity));
+    }
+
+    /// Show a notification in the message bar until it expires.
+    ///
+    /// See [the `notification` module](crate::notification) for more.
+    pub fn notify_with(&mut self, notification: Notification) {
+        notification::push(self.mode.as_ptr(), notification);
+    }
+
+    /// Remove all the notifications of this mode from the message bar.
+    pub fn clear_notifications(&mut self) {
+        notification::clear(self.mode.as_ptr());
+    }
+
+    /// Whether this mode has requested a line be selected after the next reload
+    /// with [`Self::select_line_after_reload`].
+    #[must_use]
+    pub fn has_pending_selection(&self) -> bool {
+        PENDING_SELECTION.with(
+            |pending| matches!(pending.get(), Some((mode, _)) if ptr::eq(mode, self.mode.as_ptr())),
+        )
+    }
+}
+
+thread_local! {
+    /// The line requested by [`Api::select_line_after_reload`]
+    /// and the mode that requested it.
+    static PENDING_SELECTION: Cell<Option<(*mut ffi::Mode, c_uint)>> = const { Cell::new(None) };
+}
+
+/// Apply the selection requested by [`Api::select_line_after_reload`],
+/// if the given mode requested one.
+///
+/// This is called whenever Rofi fetches the number of entries in a mode,
+/// which it does at the start of reloading the list.
+pub(crate) fn apply_pending_selection(mode: *const ffi::Mode) {
+    let line = match PENDING_SELECTION.with(Cell::get) {
+        Some((requester, line)) if ptr::eq(requester, mode) => line,
+        _ => return,
+    };
+    PENDING_SELECTION.with(|pending| pending.set(None));
+
+    // The list is only filtered after the number of entries is fetched,
+    // and selecting a line before then would be overwritten,
+    // so wait until Rofi is idle.
+    let data = line as usize as glib_sys::gpointer;
+    unsafe { glib_sys::g_idle_add(Some(select_line), data) };
+}
+
+unsafe extern "C" fn select_line(line: glib_sys::gpointer) -> glib_sys::gboolean {
+    let state = unsafe { rofi_view_get_active() };
+    if !state.is_null() {
+        unsafe { rofi_view_set_selected_line(state, line as usize as c_uint) };
+        unsafe { rofi_view_queue_redraw() };
+    }
+    glib_sys::G_SOURCE_REMOVE
+}
+
+/// Information about a mode loaded by Rofi,
+/// as returned by [`Api::modes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ModeInfo {
+    /// The index of the mode, for use with [`Action::SetMode`](crate::Action::SetMode).
+    pub index: u16,
+    /// The name the mode was loaded with, such as `window` or `drun`.
+    pub name: String,
+    /// The display name of the mode, if it has one.
+    pub display_name: Option<String>,
+    /// Whether this is the mode the [`Api`] belongs to.
+    pub is_self: bool,
+}
+
+/// Iterate over the modes Rofi has enabled, along with their indices.
+fn enabled_modes() -> impl Iterator<Item = (c_uint, *const ffi::Mode)> {
+    let count = unsafe { rofi_get_num_enabled_modes() };
+    (0..count)
+        .map(|i| (i, unsafe { rofi_get_mode(i) }))
+        .filter(|(_, mode)| !mode.is_null())
+}
+
+/// Get the mode of the view currently shown by Rofi,
+/// or null if there is none.
+fn current_mode() -> *const ffi::Mode {
+    let state = unsafe { rofi_view_get_active() };
+    if state.is_null() {
+        return ptr::null();
+    }
+    unsafe { rofi_view_get_mode(state) }
+}
+
+/// Get the index of the active mode with the given name.
+pub(crate) fn mode_index(name: &str) -> Option<u16> {
+    unsafe { find_mode(enabled_modes(), name) }
+}
+
+/// Get the index of the mode with the given name.
+///
+/// # Safety
+///
+/// `modes` must yield valid modes.
+unsafe fn find_mode<I>(mut modes: I, name: &str) -> Option<u16>
+where
+    I: Iterator<Item = (c_uint, *const ffi::Mode)>,
+{
+    modes
+        .find(|&(_, mode)| unsafe { c_str((*mode).name) }.as_deref() == Some(name))
+        .and_then(|(i, _)| u16::try_from(i).ok())
+}
+
+/// Whether the given mode, which may be null, is Rofi's `combi` mode.
+///
+/// # Safety
+///
+/// `mode` must be null or valid.
+unsafe fn is_combi(mode: *const ffi::Mode) -> bool {
+    !mode.is_null() && unsafe { c_str((*mode).name) }.as_deref() == Some("combi")
+}
+
+/// Copy a C string owned by Rofi, if it isn't null.
+unsafe fn c_str(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    Some(unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into())
+}
+
+// Functions exported by Rofi but not yet bound by `rofi-plugin-sys`.
+extern "C" {
+    fn rofi_get_num_enabled_modes() -> c_uint;
+    fn rofi_get_mode(index: c_uint) -> *const ffi::Mode;
+    fn rofi_view_get_active() -> *mut c_void;
+    fn rofi_view_get_mode(state: *mut c_void) -> *const ffi::Mode;
+    fn rofi_view_set_selected_line(state: *mut c_void, selected_line: c_uint);
+    fn rofi_view_queue_redraw();
 }
 
 /// A request sent to the icon fetcher.
@@ -242,8 +497,15 @@ impl Display for IconNotFound {
 impl Error for IconNotFound {}
 
 use crate::ffi;
+use crate::notification;
+use crate::notification::Notification;
+use crate::notification::Severity;
 use crate::String;
+use pango::glib::ffi as glib_sys;
+use std::borrow::Cow;
+use std::cell::Cell;
 use std::error::Error;
+use std::ffi::c_void;
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::fmt;
@@ -251,9 +513,69 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt::Write as _;
 use std::marker::PhantomData;
+use std::os::raw::c_char;
 use std::os::raw::c_int;
+use std::os::raw::c_uint;
 use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
 use std::ptr;
 use std::slice;
 use std::str;
+
+#[cfg(test)]
+mod tests {
+    fn with_modes<F: FnOnce(&[(c_uint, *const ffi::Mode)])>(f: F) {
+        let mut modes = [ffi::Mode::default(); 3];
+        modes[0].name = b"window\0".as_ptr() as *mut c_char;
+        modes[1].name = b"drun\0".as_ptr() as *mut c_char;
+        modes[1].display_name = b"apps\0".as_ptr() as *mut c_char;
+        modes[2].name = b"combi\0".as_ptr() as *mut c_char;
+        let modes: Vec<_> = (0..)
+            .zip(modes.iter().map(|mode| -> *const ffi::Mode { mode }))
+            .collect();
+        f(&modes);
+    }
+
+    #[test]
+    fn mode_index() {
+        with_modes(|modes| {
+            let find = |name| unsafe { find_mode(modes.iter().copied(), name) };
+            assert_eq!(find("window"), Some(0));
+            assert_eq!(find("combi"), Some(2));
+            assert_eq!(find("apps"), None);
+            assert_eq!(find("ssh"), None);
+        });
+    }
+
+    #[test]
+    fn modes() {
+        with_modes(|modes| {
+            let api = unsafe { Api::new(ptr::NonNull::new(modes[1].1 as *mut _).unwrap()) };
+            let infos = unsafe { api.mode_infos(modes.iter().copied()) };
+            let names: Vec<_> = infos.iter().map(|info| info.name.as_str()).collect();
+            assert_eq!(names, ["window", "drun", "combi"]);
+            assert_eq!(infos[1].index, 1);
+            assert_eq!(infos[0].display_name, None);
+            assert_eq!(infos[1].display_name.as_deref(), Some("apps"));
+            assert!(!infos[0].is_self);
+            assert!(infos[1].is_self);
+        });
+    }
+
+    #[test]
+    fn combi() {
+        with_modes(|modes| unsafe {
+            assert!(!is_combi(ptr::null()));
+            assert!(!is_combi(modes[0].1));
+            assert!(is_combi(modes[2].1));
+        });
+    }
+
+    use super::find_mode;
+    use super::is_combi;
+    use crate::ffi;
+    use crate::Api;
+    use std::os::raw::c_char;
+    use std::os::raw::c_uint;
+    use std::ptr;
+}
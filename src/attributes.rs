@@ -0,0 +1,292 @@
+//! Text attributes of entries, such as weight, colour and underline.
+
+/// A collection of attributes that can be applied to text.
+///
+/// Attributes apply to a range of the text given in bytes,
+/// which can be set with methods like [`Self::with_weight`] or [`Self::push_range`].
+/// Attributes added without a range apply to the whole text.
+///
+/// ```
+/// use rofi_mode::pango;
+/// use rofi_mode::Attributes;
+///
+/// let attributes = Attributes::new()
+///     .with_weight(0..4, pango::Weight::Bold)
+///     .with_strikethrough(5.., true);
+/// let ranges: Vec<_> = attributes
+///     .iter()
+///     .map(|attribute| (attribute.start_index(), attribute.end_index()))
+///     .collect();
+/// assert_eq!(ranges, [(0, 4), (5, pango::ATTR_INDEX_TO_TEXT_END)]);
+/// ```
+#[derive(Debug)]
+pub struct Attributes {
+    list: *mut glib_sys::GList,
+}
+
+unsafe impl Send for Attributes {}
+unsafe impl Sync for Attributes {}
+
+impl Attributes {
+    /// Create a new empty collection of attributes.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            list: ptr::null_mut(),
+        }
+    }
+
+    /// An an attribute to the list.
+    pub fn push<A: Into<pango::Attribute>>(&mut self, attribute: A) {
+        let attribute: pango::Attribute = attribute.into();
+        // Convert the attribute into its raw form without copying.
+        let raw: *mut pango_sys::PangoAttribute = ManuallyDrop::new(attribute).to_glib_none_mut().0;
+        self.list = unsafe { glib_sys::g_list_prepend(self.list, raw.cast()) };
+    }
+
+    /// Add an attribute to the list,
+    /// applying it to the given range of bytes of the text.
+    pub fn push_range<R, A>(&mut self, range: R, attribute: A)
+    where
+        R: RangeBounds<usize>,
+        A: Into<pango::Attribute>,
+    {
+        let mut attribute: pango::Attribute = attribute.into();
+        let (start, end) = byte_range(range);
+        attribute.set_start_index(start);
+        attribute.set_end_index(end);
+        self.push(attribute);
+    }
+
+    /// Add an attribute applying to the given range of bytes of the text.
+    #[must_use]
+    pub fn with<R, A>(mut self, range: R, attribute: A) -> Self
+    where
+        R: RangeBounds<usize>,
+        A: Into<pango::Attribute>,
+    {
+        self.push_range(range, attribute);
+        self
+    }
+
+    /// Set the font weight of the given range of bytes of the text.
+    #[must_use]
+    pub fn with_weight<R: RangeBounds<usize>>(self, range: R, weight: pango::Weight) -> Self {
+        self.with(range, pango::AttrInt::new_weight(weight))
+    }
+
+    /// Set the foreground colour of the given range of bytes of the text.
+    #[must_use]
+    pub fn with_foreground<R: RangeBounds<usize>>(self, range: R, color: pango::Color) -> Self {
+        let attribute = pango::AttrColor::new_foreground(color.red(), color.green(), color.blue());
+        self.with(range, attribute)
+    }
+
+    /// Set the underline style of the given range of bytes of the text.
+    #[must_use]
+    pub fn with_underline<R: RangeBounds<usize>>(
+        self,
+        range: R,
+        underline: pango::Underline,
+    ) -> Self {
+        self.with(range, pango::AttrInt::new_underline(underline))
+    }
+
+    /// Set whether the given range of bytes of the text is struck through.
+    #[must_use]
+    pub fn with_strikethrough<R: RangeBounds<usize>>(self, range: R, strikethrough: bool) -> Self {
+        self.with(range, pango::AttrInt::new_strikethrough(strikethrough))
+    }
+
+    /// Set the font size of the given range of bytes of the text, in points.
+    #[must_use]
+    pub fn with_font_size<R: RangeBounds<usize>>(self, range: R, points: f64) -> Self {
+        let size = (points * f64::from(pango::SCALE)).round() as i32;
+        self.with(range, pango::AttrSize::new(size))
+    }
+
+    /// Whether the list contains no attributes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_null()
+    }
+
+    /// Iterate over copies of the attributes in the order they were added.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            node: unsafe { glib_sys::g_list_last(self.list) },
+            _attributes: PhantomData,
+        }
+    }
+
+    /// Give up ownership of the underlying `GList` of `PangoAttribute`s.
+    pub(crate) fn into_raw(self) -> *mut glib_sys::GList {
+        ManuallyDrop::new(self).list
+    }
+}
+
+impl Default for Attributes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for Attributes {
+    fn clone(&self) -> Self {
+        unsafe extern "C" fn copy_attribute(
+            ptr: glib_sys::gconstpointer,
+            _user_data: glib_sys::gpointer,
+        ) -> glib_sys::gpointer {
+            unsafe { pango_sys::pango_attribute_copy(ptr.cast()) }.cast()
+        }
+
+        let list =
+            unsafe { glib_sys::g_list_copy_deep(self.list, Some(copy_attribute), ptr::null_mut()) };
+        Self { list }
+    }
+}
+
+impl From<pango::Attribute> for Attributes {
+    fn from(attribute: pango::Attribute) -> Self {
+        let mut this = Self::new();
+        this.push(attribute);
+        this
+    }
+}
+
+impl From<&pango::AttrList> for Attributes {
+    fn from(list: &pango::AttrList) -> Self {
+        let mut this = Self::new();
+        // Filtering is the only way to visit every attribute
+        // that is available in all supported versions of Pango.
+        _ = list.filter(|attribute| {
+            this.push(attribute.clone());
+            false
+        });
+        this
+    }
+}
+
+impl From<pango::AttrList> for Attributes {
+    fn from(list: pango::AttrList) -> Self {
+        Self::from(&list)
+    }
+}
+
+impl From<&Attributes> for pango::AttrList {
+    fn from(attributes: &Attributes) -> Self {
+        let list = Self::new();
+        for attribute in attributes {
+            list.insert(attribute);
+        }
+        list
+    }
+}
+
+impl From<Attributes> for pango::AttrList {
+    fn from(attributes: Attributes) -> Self {
+        Self::from(&attributes)
+    }
+}
+
+impl Drop for Attributes {
+    fn drop(&mut self) {
+        unsafe extern "C" fn free_attribute(ptr: *mut c_void) {
+            unsafe { pango_sys::pango_attribute_destroy(ptr.cast()) }
+        }
+
+        unsafe { glib_sys::g_list_free_full(self.list, Some(free_attribute)) };
+    }
+}
+
+impl<A: Into<pango::Attribute>> Extend<A> for Attributes {
+    fn extend<T: IntoIterator<Item = A>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|item| self.push(item));
+    }
+}
+
+impl<A: Into<pango::Attribute>> FromIterator<A> for Attributes {
+    fn from_iter<T: IntoIterator<Item = A>>(iter: T) -> Self {
+        let mut this = Self::new();
+        this.extend(iter);
+        this
+    }
+}
+
+impl<'a> IntoIterator for &'a Attributes {
+    type Item = pango::Attribute;
+    type IntoIter = Iter<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over copies of the attributes in an [`Attributes`],
+/// created by [`Attributes::iter`].
+#[derive(Debug)]
+pub struct Iter<'a> {
+    node: *mut glib_sys::GList,
+    _attributes: PhantomData<&'a Attributes>,
+}
+
+unsafe impl Send for Iter<'_> {}
+unsafe impl Sync for Iter<'_> {}
+
+impl Iterator for Iter<'_> {
+    type Item = pango::Attribute;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.node.is_null() {
+            return None;
+        }
+        let node = unsafe { &*self.node };
+        self.node = node.prev;
+        Some(unsafe { from_glib_none(node.data.cast::<pango_sys::PangoAttribute>()) })
+    }
+}
+
+/// Convert a range of bytes into the start and end indices used by Pango.
+fn byte_range<R: RangeBounds<usize>>(range: R) -> (u32, u32) {
+    let index = |i: usize| u32::try_from(i).unwrap_or(u32::MAX);
+    let start = match range.start_bound() {
+        Bound::Included(&start) => index(start),
+        Bound::Excluded(&start) => index(start).saturating_add(1),
+        Bound::Unbounded => pango::ATTR_INDEX_FROM_TEXT_BEGINNING,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => index(end).saturating_add(1),
+        Bound::Excluded(&end) => index(end),
+        Bound::Unbounded => pango::ATTR_INDEX_TO_TEXT_END,
+    };
+    (start, end)
+}
+
+use pango::ffi as pango_sys;
+use pango::glib::ffi as glib_sys;
+use pango::glib::translate::from_glib_none;
+use pango::glib::translate::ToGlibPtrMut;
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ops::Bound;
+use std::ops::RangeBounds;
+use std::ptr;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(not(miri))]
+    fn attr_list_round_trip() {
+        let attributes = Attributes::new()
+            .with_weight(..3, pango::Weight::Bold)
+            .with_underline(2..=4, pango::Underline::Single);
+        let list = pango::AttrList::from(&attributes);
+        let ranges: Vec<_> = Attributes::from(list)
+            .iter()
+            .map(|attribute| (attribute.start_index(), attribute.end_index()))
+            .collect();
+        assert_eq!(ranges, [(0, 3), (2, 5)]);
+    }
+
+    use super::Attributes;
+}
@@ -0,0 +1,215 @@
+//! Shortening entry text to fit the width of the list.
+//!
+//! An [`Ellipsizer`] measures text with Pango using the font of the current theme
+//! and replaces the part that doesn't fit with an ellipsis,
+//! at the [start, middle or end](Position) of the text.
+//!
+//! When used on [entries](Ellipsizer::entries),
+//! the full text stays available for matching, completion and identification,
+//! so the user can still search for the hidden part.
+//!
+//! ```
+//! use rofi_mode::ellipsize::Ellipsizer;
+//! use rofi_mode::ellipsize::Position;
+//! use rofi_mode::ellipsize::Width;
+//! use rofi_mode::list::Entry;
+//!
+//! fn entries(paths: Vec<String>) -> Vec<Entry<()>> {
+//!     let ellipsizer = Ellipsizer::new(Position::Middle, Width::Chars(60));
+//!     let entries = paths.into_iter().map(|path| Entry::new(path, ())).collect();
+//!     ellipsizer.entries(entries)
+//! }
+//! ```
+
+/// A shortener of text that is too wide for the list.
+///
+/// See [the module documentation](self) for more.
+#[derive(Debug, Clone)]
+pub struct Ellipsizer {
+    position: Position,
+    width: Width,
+    font: Option<std::string::String>,
+}
+
+impl Ellipsizer {
+    /// Create an ellipsizer that shortens text at the given position
+    /// to fit within the given width.
+    #[must_use]
+    pub fn new(position: Position, width: Width) -> Self {
+        Self {
+            position,
+            width,
+            font: None,
+        }
+    }
+
+    /// Set the font the text is measured with,
+    /// as a Pango font description like `Sans 12`.
+    ///
+    /// By default the font of the current theme's `element-text` is used.
+    #[must_use]
+    pub fn with_font<F: Into<std::string::String>>(mut self, font: F) -> Self {
+        self.font = Some(font.into());
+        self
+    }
+
+    /// Get where text is shortened.
+    #[must_use]
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Get the width text is shortened to fit within.
+    #[must_use]
+    pub fn width(&self) -> Width {
+        self.width
+    }
+
+    /// Shorten text to fit within the width,
+    /// returning it unchanged if it already fits.
+    #[must_use]
+    pub fn ellipsize(&self, text: &str) -> String {
+        let measure = Measure::new(self.font.as_deref());
+        let max_width = self.max_width(&measure);
+        match ellipsize_with(text, self.position, max_width, |text| measure.width(text)) {
+            Some(ellipsized) => ellipsized.into(),
+            None => text.into(),
+        }
+    }
+
+    /// Shorten the text of entries to fit within the width.
+    ///
+    /// For each entry that is shortened,
+    /// its [search text](Entry::search), [completion](Entry::completion) and [ID](Entry::id)
+    /// are set from its full text if they were not already set.
+    /// Markup is removed from the shortened text,
+    /// as are the entry's [attributes](Entry::attributes),
+    /// since they would no longer line up with the text.
+    #[must_use]
+    pub fn entries<T>(&self, entries: Vec<Entry<T>>) -> Vec<Entry<T>> {
+        let measure = Measure::new(self.font.as_deref());
+        let max_width = self.max_width(&measure);
+        entries
+            .into_iter()
+            .map(|mut entry| {
+                let text = entry.plain_text();
+                let ellipsized =
+                    ellipsize_with(&text, self.position, max_width, |text| measure.width(text));
+                if let Some(ellipsized) = ellipsized {
+                    entry.search.get_or_insert_with(|| text.clone());
+                    entry.completion.get_or_insert_with(|| text.clone());
+                    entry.id.get_or_insert(text);
+                    entry.text = ellipsized.into();
+                    entry.markup = false;
+                    entry.attributes = Attributes::new();
+                }
+                entry
+            })
+            .collect()
+    }
+
+    fn max_width(&self, measure: &Measure) -> i32 {
+        let chars = |chars: u16| i32::from(chars) * measure.char_width();
+        match self.width {
+            Width::Pixels(pixels) => i32::from(pixels),
+            Width::Chars(count) => chars(count),
+            Width::Theme => window_width().unwrap_or_else(|| chars(DEFAULT_CHARS)),
+        }
+    }
+}
+
+/// Where an [`Ellipsizer`] shortens text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Position {
+    /// Remove text from the start, as in `…/bin/rofi`.
+    Start,
+    /// Remove text from the middle, as in `/usr/…/rofi`.
+    Middle,
+    /// Remove text from the end, as in `/usr/local/…`.
+    End,
+}
+
+/// The width an [`Ellipsizer`] shortens text to fit within.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Width {
+    /// The width of the window set by the current theme.
+    ///
+    /// Entries are slightly narrower than the window because of its padding,
+    /// so this is only an approximation.
+    /// If the theme doesn't set a width, 60 characters is used.
+    Theme,
+    /// A number of pixels.
+    Pixels(u16),
+    /// A number of average-width characters of the font.
+    Chars(u16),
+}
+
+/// The number of characters used when the theme doesn't set a width.
+const DEFAULT_CHARS: u16 = 60;
+
+/// Shorten text to fit within a maximum width,
+/// returning [`None`] if it already fits.
+pub(crate) fn ellipsize_with<M: FnMut(&str) -> i32>(
+    text: &str,
+    position: Position,
+    max_width: i32,
+    mut measure: M,
+) -> Option<std::string::String> {
+    if measure(text) <= max_width {
+        return None;
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let candidate = |kept: usize| -> std::string::String {
+        let start = |n: usize| chars[..n].iter().collect::<std::string::String>();
+        let end = |n: usize| {
+            chars[chars.len() - n..]
+                .iter()
+                .collect::<std::string::String>()
+        };
+        match position {
+            Position::Start => format!("…{}", end(kept).trim_start()),
+            Position::Middle => {
+                let head = start(kept - kept / 2);
+                let tail = end(kept / 2);
+                format!("{}…{}", head.trim_end(), tail.trim_start())
+            }
+            Position::End => format!("{}…", start(kept).trim_end()),
+        }
+    };
+
+    // Find the most characters that can be kept.
+    let (mut low, mut high) = (0, chars.len());
+    while low < high {
+        let mid = (low + high + 1) / 2;
+        if measure(&candidate(mid)) <= max_width {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Some(candidate(low))
+}
+
+use crate::list::Entry;
+use crate::measure::window_width;
+use crate::measure::Measure;
+use crate::Attributes;
+use crate::String;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn positions() {
+        let measure = |text: &str| text.chars().count() as i32;
+        let ellipsize = |position| ellipsize_with("/usr/local/bin/rofi", position, 11, measure);
+        assert_eq!(ellipsize(Position::Start).unwrap(), "…l/bin/rofi");
+        assert_eq!(ellipsize(Position::Middle).unwrap(), "/usr/…/rofi");
+        assert_eq!(ellipsize(Position::End).unwrap(), "/usr/local…");
+        assert_eq!(ellipsize_with("rofi", Position::End, 11, measure), None);
+    }
+
+    use super::ellipsize_with;
+    use super::Position;
+}
@@ -0,0 +1,218 @@
+//! Remembering which entries the user selects, to rank them by frecency.
+//!
+//! A [`History`] records each time an entry is selected
+//! and scores entries by a combination of how frequently and how recently they were selected,
+//! similar to Rofi's built-in `run` and `drun` modes.
+//! It is stored in the mode's [state directory](crate::state) as `history.json`.
+//!
+//! A [`ListMode`] can opt in to history by setting [`Context::history`] in its `init` function;
+//! the list then records every [`Event::Ok`]
+//! and keeps its entries sorted with the highest scoring entries first.
+//! Entries are identified by their [identity](Entry::identity).
+//!
+//! ```no_run
+//! use rofi_mode::history::History;
+//! use rofi_mode::list;
+//!
+//! struct Mode;
+//!
+//! impl rofi_mode::ListMode<'_> for Mode {
+//!     const NAME: &'static str = "an-example-list-mode\0";
+//!     type Data = ();
+//!
+//!     fn init(cx: &mut list::Context<'_, ()>) -> Result<Self, ()> {
+//!         cx.history = Some(History::load(&cx.api));
+//!         cx.entries.extend(["firefox", "thunderbird", "gimp"].map(list::Entry::from));
+//!         Ok(Self)
+//!     }
+//!     # fn select(
+//!     #     &mut self,
+//!     #     _cx: &mut list::Context<'_, ()>,
+//!     #     _selected: usize,
+//!     #     _alt: bool,
+//!     #     _input: &mut rofi_mode::String,
+//!     # ) -> rofi_mode::Action {
+//!     #     rofi_mode::Action::Exit
+//!     # }
+//! }
+//! ```
+//!
+//! This module requires the `state` feature.
+//!
+//! [`ListMode`]: crate::ListMode
+//! [`Event::Ok`]: crate::Event::Ok
+//! [`Context::history`]: crate::list::Context::history
+
+/// A persistent record of the entries a user has selected.
+///
+/// See [the module documentation](self) for more.
+#[derive(Debug)]
+pub struct History {
+    state: State<Records>,
+}
+
+impl History {
+    /// Load the history of the current mode,
+    /// starting with an empty history if it can't be loaded.
+    #[must_use]
+    pub fn load(api: &Api<'_>) -> Self {
+        Self {
+            state: State::load_named(api, "history"),
+        }
+    }
+
+    /// Record that the entry with the given identity was selected now.
+    pub fn record(&mut self, id: &str) {
+        self.record_at(id, now());
+    }
+
+    /// Record that the entry with the given identity was selected
+    /// at the given time in seconds since the Unix epoch.
+    pub fn record_at(&mut self, id: &str, time: u64) {
+        let record = self.state.0.entry(id.to_owned()).or_default();
+        record.count = record.count.saturating_add(1);
+        record.visits.push(time);
+        if record.visits.len() > MAX_VISITS {
+            record.visits.remove(0);
+        }
+    }
+
+    /// Remove the entry with the given identity from the history.
+    pub fn forget(&mut self, id: &str) {
+        if self.state.0.contains_key(id) {
+            self.state.0.remove(id);
+        }
+    }
+
+    /// Remove every entry from the history.
+    pub fn clear(&mut self) {
+        if !self.state.0.is_empty() {
+            self.state.0.clear();
+        }
+    }
+
+    /// Get the frecency score of the entry with the given identity.
+    ///
+    /// Entries that have never been selected have a score of zero.
+    #[must_use]
+    pub fn score(&self, id: &str) -> f64 {
+        self.score_at(id, now())
+    }
+
+    /// Get the frecency score of the entry with the given identity
+    /// as of the given time in seconds since the Unix epoch.
+    #[must_use]
+    pub fn score_at(&self, id: &str, now: u64) -> f64 {
+        self.state.0.get(id).map_or(0.0, |record| record.score(now))
+    }
+
+    /// Stably sort a list of entries by descending score.
+    pub fn sort<T>(&self, entries: &mut [Entry<T>]) {
+        let now = now();
+        // Scores are never negative or NaN,
+        // so comparing their bits is equivalent to comparing the floats.
+        entries.sort_by_cached_key(|entry| {
+            Reverse(self.score_at(entry.identity().as_str(), now).to_bits())
+        });
+    }
+
+    /// Save the history to disk.
+    ///
+    /// This happens automatically when the history is dropped,
+    /// but calling this allows handling errors.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the history could not be written.
+    pub fn save(&mut self) -> io::Result<()> {
+        self.state.save()
+    }
+}
+
+/// The maximum number of visits remembered for each entry.
+const MAX_VISITS: usize = 10;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Records(HashMap<String, Record>);
+
+impl Schema for Records {}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Record {
+    /// The total number of times the entry was selected.
+    count: u32,
+    /// The times of the most recent selections, oldest first.
+    visits: Vec<u64>,
+}
+
+impl Record {
+    /// Calculate the frecency score of the entry:
+    /// its total selection count
+    /// multiplied by the average weight of its recent selections,
+    /// where more recent selections weigh more.
+    fn score(&self, now: u64) -> f64 {
+        let (visits, total_weight) = self.visits.iter().fold((0, 0), |(visits, total), &time| {
+            (visits + 1, total + weight(now.saturating_sub(time)))
+        });
+        if visits == 0 {
+            return 0.0;
+        }
+        f64::from(self.count) * f64::from(total_weight) / f64::from(visits)
+    }
+}
+
+/// The weight of a selection made the given number of seconds ago.
+fn weight(age: u64) -> u32 {
+    const DAY: u64 = 24 * 60 * 60;
+    match age / DAY {
+        0..=3 => 100,
+        4..=13 => 70,
+        14..=30 => 50,
+        31..=89 => 30,
+        _ => 10,
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+use crate::list::Entry;
+use crate::state::Schema;
+use crate::state::State;
+use crate::Api;
+use serde::Deserialize;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io;
+use std::time::SystemTime;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[allow(clippy::float_cmp)] // The expected scores are exactly representable.
+    fn frecency() {
+        const DAY: u64 = 24 * 60 * 60;
+        let now = 1000 * DAY;
+
+        let record = |visits: &[u64]| Record {
+            count: visits.len().try_into().unwrap(),
+            visits: visits.iter().map(|days_ago| now - days_ago * DAY).collect(),
+        };
+
+        assert_eq!(Record::default().score(now), 0.0);
+        assert_eq!(record(&[0]).score(now), 100.0);
+        assert_eq!(record(&[0, 0]).score(now), 200.0);
+        assert_eq!(record(&[100]).score(now), 10.0);
+
+        // Recent selections outweigh frequent old ones.
+        assert!(record(&[1, 2]).score(now) > record(&[100, 100, 100, 100, 100]).score(now));
+        // Frequent selections outweigh a single recent one.
+        assert!(record(&[10, 10]).score(now) > record(&[0]).score(now));
+    }
+
+    use super::Record;
+}
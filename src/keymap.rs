@@ -0,0 +1,386 @@
+//! Named actions bound to Rofi's custom commands.
+//!
+//! Rofi reports the `kb-custom-<n>` bindings as [`Event::CustomCommand`]s
+//! with only a number to identify them.
+//! A [`Keymap`] gives each number a name, a description and a typed action,
+//! so that handling a custom command is a lookup with [`Keymap::action`]
+//! and the bindings can be listed for the user with [`Keymap::cheat_sheet`].
+//!
+//! The cheat sheet shows the keys the user has actually bound to each command.
+//! They are read from the arguments Rofi was started with,
+//! falling back to Rofi's default bindings.
+//! Bindings set in Rofi's configuration file are only known
+//! after calling [`Keymap::load_keys`],
+//! which runs `rofi -dump-config` and blocks until it finishes,
+//! so a mode that wants them should call it once in its `init` function.
+//!
+//! ```
+//! use rofi_mode::keymap::Keymap;
+//! use rofi_mode::Action;
+//! use rofi_mode::Event;
+//!
+//! #[derive(Clone, Copy)]
+//! enum Command {
+//!     Copy,
+//!     Edit,
+//!     Help,
+//! }
+//!
+//! struct Mode {
+//!     keymap: Keymap<Command>,
+//!     show_help: bool,
+//! }
+//!
+//! impl Mode {
+//!     fn new() -> Self {
+//!         let keymap = Keymap::new()
+//!             .bind(0, "copy", "Copy the entry to the clipboard", Command::Copy)
+//!             .bind(1, "edit", "Edit the entry", Command::Edit)
+//!             .bind(9, "help", "Show this help", Command::Help);
+//!         keymap.load_keys();
+//!         Self { keymap, show_help: false }
+//!     }
+//!
+//!     fn react(&mut self, event: Event) -> Action {
+//!         match self.keymap.action(event) {
+//!             Some(Command::Copy) => { /* ... */ }
+//!             Some(Command::Edit) => { /* ... */ }
+//!             Some(Command::Help) => self.show_help = !self.show_help,
+//!             None => {}
+//!         }
+//!         Action::Reload
+//!     }
+//!
+//!     fn message(&self) -> rofi_mode::String {
+//!         if self.show_help {
+//!             self.keymap.cheat_sheet()
+//!         } else {
+//!             rofi_mode::String::new()
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`Event::CustomCommand`]: crate::Event::CustomCommand
+
+/// A set of named actions bound to custom commands.
+///
+/// See [the module documentation](self) for more.
+#[derive(Debug, Clone)]
+pub struct Keymap<A> {
+    bindings: Vec<Binding<A>>,
+}
+
+impl<A> Keymap<A> {
+    /// Create a keymap with no bindings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Bind an action to the custom command with the given number,
+    /// as reported by [`Event::CustomCommand`].
+    ///
+    /// Command `n` is triggered by Rofi's `kb-custom-<n + 1>` binding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number is not in the range [0, 18]
+    /// or if the command is already bound.
+    #[must_use]
+    pub fn bind<N, D>(mut self, number: u8, name: N, description: D, action: A) -> Self
+    where
+        N: Into<String>,
+        D: Into<String>,
+    {
+        assert!(
+            number < CUSTOM_COMMANDS,
+            "custom command number out of range"
+        );
+        assert!(
+            self.binding(number).is_none(),
+            "custom command {number} is already bound",
+        );
+        self.bindings.push(Binding {
+            number,
+            name: name.into(),
+            description: description.into(),
+            action,
+        });
+        self
+    }
+
+    /// Get the bindings in the order they were added.
+    #[must_use]
+    pub fn bindings(&self) -> &[Binding<A>] {
+        &self.bindings
+    }
+
+    /// Get the binding of the custom command with the given number.
+    #[must_use]
+    pub fn binding(&self, number: u8) -> Option<&Binding<A>> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.number == number)
+    }
+
+    /// Get the binding with the given name.
+    #[must_use]
+    pub fn named(&self, name: &str) -> Option<&Binding<A>> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.name.as_str() == name)
+    }
+
+    /// Get the action bound to the event,
+    /// if it is a custom command with a binding.
+    #[must_use]
+    pub fn action(&self, event: Event) -> Option<&A> {
+        match event {
+            Event::CustomCommand { number, .. } => Some(&self.binding(number)?.action),
+            _ => None,
+        }
+    }
+
+    /// Read the keys bound to custom commands from Rofi's configuration file,
+    /// for [`Self::cheat_sheet`] and [`Binding::key`] to use on this thread.
+    ///
+    /// This runs the executable of the current Rofi process with `-dump-config`
+    /// and blocks until it finishes.
+    /// Later calls on the same thread do nothing.
+    pub fn load_keys(&self) {
+        CONFIG_KEYS.with(|keys| {
+            keys.borrow_mut().get_or_insert_with(config_keys);
+        });
+    }
+
+    /// Render a cheat sheet listing the keys, names and descriptions of every binding,
+    /// one per line, as markup for the message bar.
+    ///
+    /// The sheet is in a monospace font so that its columns line up.
+    /// It only includes bindings from Rofi's configuration file
+    /// once [`Self::load_keys`] has been called.
+    #[must_use]
+    pub fn cheat_sheet(&self) -> String {
+        let keys: Vec<std::string::String> = self
+            .bindings
+            .iter()
+            .map(|binding| custom_key(binding.number))
+            .collect();
+        cheat_sheet(&self.bindings, &keys)
+    }
+}
+
+/// Render a cheat sheet of bindings with the given keys.
+fn cheat_sheet<A>(bindings: &[Binding<A>], keys: &[std::string::String]) -> String {
+    let width = keys
+        .iter()
+        .map(|key| key.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut sheet = std::string::String::new();
+    for (binding, key) in bindings.iter().zip(keys) {
+        if !sheet.is_empty() {
+            sheet.push('\n');
+        }
+        sheet.push_str(key);
+        sheet.extend(iter::repeat(' ').take(width - key.chars().count() + 2));
+        sheet.push_str(&binding.name);
+        if !binding.description.is_empty() {
+            sheet.push_str(": ");
+            sheet.push_str(&binding.description);
+        }
+    }
+    Markup::new().monospace(sheet).into_string()
+}
+
+impl<A> Default for Keymap<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An action bound to a custom command in a [`Keymap`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Binding<A> {
+    /// The number of the custom command, as reported by [`Event::CustomCommand`].
+    pub number: u8,
+    /// The name of the action.
+    pub name: String,
+    /// A description of what the action does.
+    pub description: String,
+    /// The action itself.
+    pub action: A,
+}
+
+impl<A> Binding<A> {
+    /// Get the keys the user has bound to the custom command,
+    /// or `unbound` if there are none.
+    ///
+    /// Bindings from Rofi's configuration file are only known
+    /// once [`Keymap::load_keys`] has been called.
+    #[must_use]
+    pub fn key(&self) -> std::string::String {
+        custom_key(self.number)
+    }
+}
+
+/// The number of custom commands supported by Rofi.
+const CUSTOM_COMMANDS: u8 = 19;
+
+/// Rofi's default bindings of `kb-custom-1` to `kb-custom-19`.
+const DEFAULT_KEYS: [&str; CUSTOM_COMMANDS as usize] = [
+    "Alt+1",
+    "Alt+2",
+    "Alt+3",
+    "Alt+4",
+    "Alt+5",
+    "Alt+6",
+    "Alt+7",
+    "Alt+8",
+    "Alt+9",
+    "Alt+0",
+    "Alt+exclam",
+    "Alt+at",
+    "Alt+numbersign",
+    "Alt+dollar",
+    "Alt+percent",
+    "Alt+dead_circumflex",
+    "Alt+ampersand",
+    "Alt+asterisk",
+    "Alt+parenleft",
+];
+
+thread_local! {
+    /// The bindings read from Rofi's configuration by [`Keymap::load_keys`].
+    static CONFIG_KEYS: RefCell<Option<Vec<Option<std::string::String>>>> = const { RefCell::new(None) };
+}
+
+/// Get the keys bound to the custom command with the given number,
+/// for displaying to the user.
+fn custom_key(number: u8) -> std::string::String {
+    let option = format!("-kb-custom-{}", number + 1);
+    let keys = arg(&option).or_else(|| {
+        CONFIG_KEYS.with(|keys| {
+            keys.borrow()
+                .as_ref()?
+                .get(usize::from(number))
+                .cloned()
+                .flatten()
+        })
+    });
+    let keys = keys.unwrap_or_else(|| DEFAULT_KEYS[usize::from(number)].to_owned());
+    if keys.is_empty() {
+        return "unbound".to_owned();
+    }
+    keys.replace(',', ", ")
+}
+
+/// Get the value of a command-line argument Rofi was started with.
+fn arg(option: &str) -> Option<std::string::String> {
+    let option = CString::new(option).ok()?;
+    let mut value: *mut c_char = ptr::null_mut();
+    if unsafe { ffi::helper::find_arg_str(option.as_ptr(), &mut value) } == 0 || value.is_null() {
+        return None;
+    }
+    Some(
+        unsafe { CStr::from_ptr(value) }
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+/// Read the custom command bindings from Rofi's configuration.
+fn config_keys() -> Vec<Option<std::string::String>> {
+    // Run the same Rofi that loaded the mode rather than whichever is on `$PATH`.
+    let rofi = env::current_exe().unwrap_or_else(|_| "rofi".into());
+    let mut command = Command::new(rofi);
+    if let Some(config) = arg("-config") {
+        command.arg("-config").arg(config);
+    }
+    let output = command
+        .arg("-dump-config")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output();
+    let mut keys = vec![None; usize::from(CUSTOM_COMMANDS)];
+    if let Ok(output) = output {
+        for line in std::string::String::from_utf8_lossy(&output.stdout).lines() {
+            if let Some((number, binding)) = parse_config_line(line) {
+                keys[number] = Some(binding.to_owned());
+            }
+        }
+    }
+    keys
+}
+
+/// Parse a `kb-custom-<n>` line of the output of `rofi -dump-config`,
+/// returning the index of the custom command and its bindings.
+///
+/// Options that are left at their default value are commented out,
+/// so the comment markers are ignored.
+fn parse_config_line(line: &str) -> Option<(usize, &str)> {
+    let line = line.trim();
+    let line = line.strip_prefix("/*").unwrap_or(line);
+    let line = line.strip_suffix("*/").unwrap_or(line).trim();
+    let (name, value) = line.strip_prefix("kb-custom-")?.split_once(':')?;
+    let number = name.parse::<usize>().ok()?.checked_sub(1)?;
+    if number >= usize::from(CUSTOM_COMMANDS) {
+        return None;
+    }
+    let value = value.trim().strip_suffix(';')?.trim();
+    let value = value.strip_prefix('"')?.strip_suffix('"')?;
+    Some((number, value))
+}
+
+use crate::ffi;
+use crate::markup::Markup;
+use crate::Event;
+use crate::String;
+use std::cell::RefCell;
+use std::env;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::iter;
+use std::os::raw::c_char;
+use std::process::Command;
+use std::process::Stdio;
+use std::ptr;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn config_lines() {
+        assert_eq!(
+            parse_config_line("\tkb-custom-1: \"Alt+c\";"),
+            Some((0, "Alt+c")),
+        );
+        assert_eq!(
+            parse_config_line("/*\tkb-custom-19: \"Alt+parenleft\";*/"),
+            Some((18, "Alt+parenleft")),
+        );
+        assert_eq!(parse_config_line("\tkb-custom-2: \"\";"), Some((1, "")));
+        assert_eq!(parse_config_line("\tkb-custom-20: \"Alt+x\";"), None);
+        assert_eq!(parse_config_line("\tkb-cancel: \"Escape\";"), None);
+    }
+
+    #[test]
+    fn cheat_sheet() {
+        let keymap = Keymap::new()
+            .bind(0, "copy", "Copy <text>", ())
+            .bind(1, "edit", "", ());
+        let keys = ["Alt+1".to_owned(), "Control+ampersand & Alt+2".to_owned()];
+        assert_eq!(
+            super::cheat_sheet(keymap.bindings(), &keys).as_str(),
+            "<tt>Alt+1                      copy: Copy &lt;text&gt;\n\
+             Control+ampersand &amp; Alt+2  edit</tt>",
+        );
+    }
+
+    use super::parse_config_line;
+    use super::Keymap;
+}
@@ -78,7 +78,7 @@
 //! # Examples
 //!
 //! - See [examples/basic] for a basic example of a non-trivial Rofi mode,
-//!     which allows the user to add to the list of entries by writing in the Rofi box.
+//!   which allows the user to add to the list of entries by writing in the Rofi box.
 //! - See [examples/file-browser] for a Rofi mode implementing a simple file browser.
 //!
 //! [`Mode`]: https://docs.rs/rofi-mode/latest/rofi_mode/trait.Mode.html
@@ -114,6 +114,49 @@ pub use string::String;
 pub mod api;
 pub use api::Api;
 
+pub mod attributes;
+pub use attributes::Attributes;
+
+pub mod list;
+pub use list::List;
+pub use list::ListMode;
+
+pub mod simple;
+pub use simple::SimpleMode;
+
+pub mod selection;
+
+pub mod wizard;
+
+pub mod widget;
+
+pub mod keymap;
+
+pub mod notification;
+
+pub mod markup;
+
+pub mod table;
+
+pub mod ellipsize;
+
+mod measure;
+
+/// Derive [`list::IntoEntry`] for a struct.
+///
+/// See [the macro's documentation](rofi_mode_derive::Entry) for more.
+#[cfg(feature = "derive")]
+pub use rofi_mode_derive::Entry;
+
+#[cfg(feature = "state")]
+pub mod state;
+
+#[cfg(feature = "state")]
+pub mod history;
+
+#[cfg(feature = "state")]
+pub mod pins;
+
 /// A mode supported by Rofi.
 ///
 /// You can implement this trait on your own type to define a mode,
@@ -130,6 +173,19 @@ pub trait Mode<'rofi>: Sized + Sync {
     /// ```
     const NAME: &'static str;
 
+    /// The name of an icon to show next to entries that don't have their own,
+    /// or a path to an image file.
+    ///
+    /// Rofi shows it for every entry for which [`Self::entry_icon`] returns [`None`],
+    /// in place of the `fallback-icon` property of the entry's widget in the theme.
+    /// It is not shown in the mode switcher.
+    ///
+    /// Like [`Self::NAME`], it must not contain nul characters,
+    /// which is checked at compile time.
+    ///
+    /// The default is [`None`], which leaves the icon up to the theme.
+    const ICON: Option<&'static str> = None;
+
     /// Initialize the mode.
     ///
     /// # Errors
@@ -143,6 +199,32 @@ pub trait Mode<'rofi>: Sized + Sync {
     #[allow(clippy::result_unit_err)]
     fn init(api: Api<'rofi>) -> Result<Self, ()>;
 
+    /// Handle the mode becoming active again
+    /// after the user switched away from it and back,
+    /// for example with [`Action::Next`], [`Action::Previous`] or [`Action::SetMode`].
+    ///
+    /// The mode is only [initialized](Self::init) the first time it becomes active,
+    /// so this is the place to refresh any data that may have gone stale in the meantime.
+    ///
+    /// The default implementation does nothing.
+    fn reactivated(&mut self) {}
+
+    /// Tear down the mode when Rofi exits.
+    ///
+    /// This is the place to flush caches, persist state or kill child processes.
+    /// The [`Api`] given to [`Self::init`] is still valid for the duration of this call,
+    /// and the mode is dropped once it returns.
+    ///
+    /// `action` is the last action the mode returned from [`Self::react`],
+    /// or the mode switch if the user switched away from it with a keybinding.
+    /// It is [`None`] if the user never interacted with the mode.
+    /// Note that when Rofi exits from a different mode,
+    /// every other mode is destroyed too,
+    /// so it is not always [`Action::Exit`].
+    ///
+    /// The default implementation does nothing.
+    fn destroy(self, _action: Option<Action>) {}
+
     /// Get the number of entries offered by the mode.
     fn entries(&mut self) -> usize;
 
@@ -192,10 +274,36 @@ pub trait Mode<'rofi>: Sized + Sync {
     /// and can be mutated to change its contents.
     fn react(&mut self, event: Event, input: &mut String) -> Action;
 
+    /// Get the hidden metadata of a particular entry in the list.
+    ///
+    /// This text is never displayed to the user,
+    /// but the default implementation of [`Self::matches`] matches against it
+    /// in addition to the entry's content,
+    /// similar to the `meta` option of Rofi's script and dmenu modes.
+    /// It is typically used to provide extra keywords an entry can be found by.
+    ///
+    /// The `line` parameter is the index of the relevant entry. It is always `< self.entries()`.
+    ///
+    /// The default implementation returns an empty string.
+    fn entry_meta(&self, _line: usize) -> String {
+        String::new()
+    }
+
     /// Find whether a specific line matches the given matcher.
     ///
     /// The `line` parameter is the index of the relevant entry. It is always `< self.entries()`.
-    fn matches(&self, line: usize, matcher: Matcher<'_>) -> bool;
+    ///
+    /// The default implementation matches against [`Self::entry_content`] and [`Self::entry_meta`],
+    /// requiring each of the user's search tokens to be found in at least one of them.
+    fn matches(&self, line: usize, matcher: Matcher<'_>) -> bool {
+        let content = self.entry_content(line);
+        let meta = self.entry_meta(line);
+        if meta.is_empty() {
+            matcher.matches_c_str(content.as_ref())
+        } else {
+            matcher.matches_any_c_str(&[content, meta])
+        }
+    }
 
     /// Get the completed value of an entry.
     ///
@@ -238,8 +346,70 @@ pub trait Mode<'rofi>: Sized + Sync {
 ///
 /// This declares a public `#[no_mangle]` static item named `mode`
 /// which Rofi reads in from your plugin cdylib.
+///
+/// As well as a type implementing [`Mode`],
+/// this macro accepts the builder of a [`SimpleMode`]
+/// whose first method call is [`name`](simple::Unnamed::name):
+///
+/// ```no_run
+/// rofi_mode::export_mode!(
+///     rofi_mode::SimpleMode::builder()
+///         .name("my-mode")
+///         .entries(|| ["an entry"])
+/// );
+/// ```
+///
+/// Since the macro reads the name at compile time,
+/// it must be set first, with a string literal:
+///
+/// ```compile_fail
+/// rofi_mode::export_mode!(
+///     rofi_mode::SimpleMode::builder()
+///         .entries(|| ["an entry"])
+///         .name("my-mode")
+/// );
+/// ```
+///
+/// The name a mode is exported under can be overridden
+/// by passing a string or C string literal after the type,
+/// allowing the same mode to be exported under different names
+/// by different plugins.
+/// The mode can find out which name it was exported under with [`Api::mode_name`].
+///
+/// ```ignore
+/// rofi_mode::export_mode!(Mode, name = "work-projects");
+/// ```
+///
+/// Like [`Mode::NAME`], the name is validated at compile time,
+/// but it does not need to be nul-terminated.
+/// It must not be empty.
 #[macro_export]
 macro_rules! export_mode {
+    ($($builder:ident)::+ () .name($name:literal) $($rest:tt)*) => {
+        const _: () = {
+            struct Factory;
+            impl $crate::simple::Factory for Factory {
+                const NAME: &'static str = ::core::concat!($name, "\0");
+                fn build() -> $crate::simple::Builder {
+                    $($builder)::+ ().name($name) $($rest)*
+                }
+            }
+            $crate::export_mode!($crate::List<'_, $crate::simple::Exported<Factory>>);
+        };
+    };
+    ($($builder:ident)::+ () $($rest:tt)*) => {
+        ::core::compile_error!(
+            "the first method called on the builder must be `.name(...)` with a string literal"
+        );
+    };
+    ($t:ty, name = $name:expr $(,)?) => {
+        #[no_mangle]
+        pub static mut mode: $crate::ffi::Mode = {
+            const NAME: $crate::__private::ModeName = $crate::__private::NameOf($name).get();
+            static BUFFER: [u8; NAME.buffer_len()] = NAME.buffer();
+            $crate::raw_mode_with_name::<fn(&()) -> $t>(NAME.to_c_str(&BUFFER))
+        };
+    };
     ($t:ty $(,)?) => {
         #[no_mangle]
         pub static mut mode: $crate::ffi::Mode = $crate::raw_mode::<fn(&()) -> $t>();
@@ -263,6 +433,26 @@ where
     <RawModeHelper<T>>::VALUE
 }
 
+/// Convert an implementation of [`Mode`] to its raw FFI `Mode` struct,
+/// exporting it under the given name instead of [`Mode::NAME`].
+///
+/// You generally do not want to call this function unless you're doing low-level stuff —
+/// most of the time the `name = ...` argument of the [`export_mode!`] macro is what you want.
+///
+/// # Panics
+///
+/// This function panics if the implementation of [`Mode`] is invalid.
+#[must_use]
+pub const fn raw_mode_with_name<T>(name: &'static CStr) -> ffi::Mode
+where
+    <[T; 0] as IntoIterator>::Item: GivesMode,
+{
+    ffi::Mode {
+        name: name.as_ptr() as *mut c_char,
+        ..<RawModeHelper<T>>::VALUE
+    }
+}
+
 mod sealed {
     use crate::Mode;
 
@@ -282,7 +472,10 @@ use sealed::GivesModeLifetime;
 struct RawModeHelper<T>(T);
 impl<T: GivesMode> RawModeHelper<T> {
     const VALUE: ffi::Mode = ffi::Mode {
-        name: assert_c_str(<<T as GivesModeLifetime<'_>>::Mode as Mode>::NAME),
+        name: {
+            assert_no_nul(<<T as GivesModeLifetime<'_>>::Mode as Mode>::ICON);
+            assert_c_str(<<T as GivesModeLifetime<'_>>::Mode as Mode>::NAME)
+        },
         _init: Some(init::<T>),
         _destroy: Some(destroy::<T>),
         _get_num_entries: Some(get_num_entries::<T>),
@@ -298,44 +491,169 @@ impl<T: GivesMode> RawModeHelper<T> {
 }
 
 const fn assert_c_str(s: &'static str) -> *mut c_char {
+    c_str(s.as_bytes()).as_ptr() as _
+}
+
+const fn assert_no_nul(s: Option<&'static str>) {
+    if let Some(s) = s {
+        let mut i = 0;
+        while i < s.len() {
+            assert!(s.as_bytes()[i] != 0, "string contains nul");
+            i += 1;
+        }
+    }
+}
+
+const fn c_str(bytes: &'static [u8]) -> &'static CStr {
     let mut i = 0;
-    while i + 1 < s.len() {
-        assert!(s.as_bytes()[i] != 0, "string contains intermediary nul");
+    while i + 1 < bytes.len() {
+        assert!(bytes[i] != 0, "string contains intermediary nul");
         i += 1;
     }
-    assert!(s.as_bytes()[i] == 0, "string is not nul-terminated");
-    s.as_ptr() as _
+    assert!(bytes[i] == 0, "string is not nul-terminated");
+    unsafe { CStr::from_bytes_with_nul_unchecked(bytes) }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    /// A name passed to `export_mode!`,
+    /// whose type determines which `get` method is called.
+    #[derive(Debug)]
+    pub struct NameOf<T>(pub T);
+
+    impl NameOf<&'static str> {
+        #[must_use]
+        pub const fn get(self) -> ModeName {
+            ModeName::Str(self.0)
+        }
+    }
+
+    impl NameOf<&'static CStr> {
+        #[must_use]
+        pub const fn get(self) -> ModeName {
+            ModeName::CStr(self.0)
+        }
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    pub enum ModeName {
+        Str(&'static str),
+        CStr(&'static CStr),
+    }
+
+    impl ModeName {
+        /// The length of the buffer needed to nul-terminate the name,
+        /// or zero if it doesn't need one.
+        #[must_use]
+        pub const fn buffer_len(self) -> usize {
+            assert!(!self.is_empty(), "mode name is empty");
+            match self {
+                Self::Str(s) if !s.is_empty() && s.as_bytes()[s.len() - 1] == 0 => 0,
+                Self::Str(s) => s.len() + 1,
+                Self::CStr(_) => 0,
+            }
+        }
+
+        /// Copy the name into a nul-terminated buffer of length `self.buffer_len()`.
+        #[must_use]
+        pub const fn buffer<const N: usize>(self) -> [u8; N] {
+            let mut buffer = [0; N];
+            if let Self::Str(s) = self {
+                let mut i = 0;
+                while i + 1 < N {
+                    assert!(s.as_bytes()[i] != 0, "string contains intermediary nul");
+                    buffer[i] = s.as_bytes()[i];
+                    i += 1;
+                }
+            }
+            buffer
+        }
+
+        const fn is_empty(self) -> bool {
+            match self {
+                Self::Str(s) => s.is_empty() || (s.len() == 1 && s.as_bytes()[0] == 0),
+                Self::CStr(s) => (unsafe { *s.as_ptr() }) == 0,
+            }
+        }
+
+        #[must_use]
+        pub const fn to_c_str(self, buffer: &'static [u8]) -> &'static CStr {
+            match self {
+                Self::CStr(s) => s,
+                Self::Str(s) if buffer.is_empty() => crate::c_str(s.as_bytes()),
+                // `buffer` was already validated by `Self::buffer`.
+                Self::Str(_) => unsafe { CStr::from_bytes_with_nul_unchecked(buffer) },
+            }
+        }
+    }
+
+    use std::ffi::CStr;
 }
 
 type ModeOf<'a, T> = <T as GivesModeLifetime<'a>>::Mode;
 
+/// The private data stored by Rofi for each mode.
+struct Private<M> {
+    mode: M,
+    /// The last action returned by the mode, passed to [`Mode::destroy`].
+    last_action: Option<Action>,
+}
+
+/// Get the private data of an initialized mode.
+unsafe fn private_data<'rofi, T: GivesMode>(
+    sw: *const ffi::Mode,
+) -> *mut Private<ModeOf<'rofi, T>> {
+    unsafe { ffi::mode_get_private_data(sw) }.cast()
+}
+
+/// The size the fallback icon of a mode is fetched at.
+///
+/// Rofi scales icons down to fit the space they are drawn in.
+const MODE_ICON_SIZE: c_int = 128;
+
 unsafe extern "C" fn init<T: GivesMode>(sw: *mut ffi::Mode) -> c_int {
-    if unsafe { ffi::mode_get_private_data(sw) }.is_null() {
-        let api = unsafe { Api::new(ptr::NonNull::from(&mut (*sw).display_name).cast()) };
-
-        let boxed: Box<ModeOf<'_, T>> =
-            match catch_panic(|| <ModeOf<'_, T>>::init(api).map(Box::new)) {
-                Ok(Ok(boxed)) => boxed,
-                Ok(Err(())) | Err(()) => return false.into(),
-            };
+    let ptr = unsafe { private_data::<T>(sw) };
+    if ptr.is_null() {
+        let api = unsafe { Api::new(ptr::NonNull::new_unchecked(sw)) };
+
+        let boxed: Box<Private<ModeOf<'_, T>>> = match catch_panic(|| <ModeOf<'_, T>>::init(api)) {
+            Ok(Ok(mode)) => Box::new(Private {
+                mode,
+                last_action: None,
+            }),
+            Ok(Err(())) | Err(()) => return false.into(),
+        };
         let ptr = Box::into_raw(boxed).cast::<c_void>();
         unsafe { ffi::mode_set_private_data(sw, ptr) };
+
+        if let Some(icon) = <ModeOf<'_, T>>::ICON {
+            // The icon was checked for nul characters by `RawModeHelper`.
+            let icon = CString::new(icon).unwrap();
+            let uid = unsafe { ffi::icon_fetcher::query(icon.as_ptr(), MODE_ICON_SIZE) };
+            unsafe { (*sw).fallback_icon_fetch_uid = uid };
+        }
+    } else {
+        // Rofi calls `init` every time the mode becomes active.
+        let mode: &mut ModeOf<'_, T> = unsafe { &mut (*ptr).mode };
+        _ = catch_panic(|| mode.reactivated());
     }
     true.into()
 }
 
 unsafe extern "C" fn destroy<T: GivesMode>(sw: *mut ffi::Mode) {
-    let ptr = unsafe { ffi::mode_get_private_data(sw) };
+    let ptr = unsafe { private_data::<T>(sw) };
     if ptr.is_null() {
         return;
     }
-    let boxed = unsafe { <Box<ModeOf<'_, T>>>::from_raw(ptr.cast()) };
-    _ = catch_panic(|| drop(boxed));
+    let boxed = unsafe { Box::from_raw(ptr) };
+    _ = catch_panic(|| boxed.mode.destroy(boxed.last_action));
+    notification::clear(sw);
     unsafe { ffi::mode_set_private_data(sw, ptr::null_mut()) };
 }
 
 unsafe extern "C" fn get_num_entries<T: GivesMode>(sw: *const ffi::Mode) -> c_uint {
-    let mode: &mut ModeOf<'_, T> = unsafe { &mut *ffi::mode_get_private_data(sw).cast() };
+    api::apply_pending_selection(sw);
+    let mode: &mut ModeOf<'_, T> = unsafe { &mut (*private_data::<T>(sw)).mode };
     catch_panic(|| mode.entries().try_into().unwrap_or(c_uint::MAX)).unwrap_or(0)
 }
 
@@ -345,8 +663,17 @@ unsafe extern "C" fn result<T: GivesMode>(
     input: *mut *mut c_char,
     selected_line: c_uint,
 ) -> c_int {
-    let mode: &mut ModeOf<'_, T> = unsafe { &mut *ffi::mode_get_private_data(sw).cast() };
+    let private = unsafe { &mut *private_data::<T>(sw) };
     let action = catch_panic(|| {
+        // Switching modes with keybindings is handled the same way by every mode.
+        if mretv & ffi::menu::NEXT != 0 {
+            return Action::Next;
+        } else if mretv & ffi::menu::PREVIOUS != 0 {
+            return Action::Previous;
+        } else if mretv & ffi::menu::QUICK_SWITCH != 0 {
+            return Action::SetMode((mretv & ffi::menu::LOWER_MASK) as u16);
+        }
+
         let selected = if selected_line == c_uint::MAX {
             None
         } else {
@@ -384,7 +711,8 @@ unsafe extern "C" fn result<T: GivesMode>(
             unsafe { String::from_raw_parts(input_ptr.cast(), len, len + 1) }
         };
 
-        let action = mode.react(event, &mut input_string);
+        let action =
+            notification::handle_event(sw, || private.mode.react(event, &mut input_string));
 
         if !input_string.is_empty() {
             *input = input_string.into_raw().cast::<c_char>();
@@ -393,9 +721,14 @@ unsafe extern "C" fn result<T: GivesMode>(
         action
     })
     .unwrap_or(Action::Exit);
+    private.last_action = Some(action);
 
     match action {
         Action::SetMode(mode) => mode.into(),
+        Action::SetModeByName(name) => match api::mode_index(name) {
+            Some(mode) => mode.into(),
+            None => ffi::RELOAD_DIALOG,
+        },
         Action::Next => ffi::NEXT_DIALOG,
         Action::Previous => ffi::PREVIOUS_DIALOG,
         Action::Reload => ffi::RELOAD_DIALOG,
@@ -411,7 +744,7 @@ unsafe extern "C" fn get_display_value<T: GivesMode>(
     attr_list: *mut *mut glib_sys::GList,
     get_entry: c_int,
 ) -> *mut c_char {
-    let mode: &ModeOf<'_, T> = unsafe { &mut *ffi::mode_get_private_data(sw).cast() };
+    let mode: &ModeOf<'_, T> = unsafe { &(*private_data::<T>(sw)).mode };
     catch_panic(|| {
         let line = selected_line as usize;
 
@@ -423,7 +756,7 @@ unsafe extern "C" fn get_display_value<T: GivesMode>(
         if !attr_list.is_null() {
             assert!(unsafe { *attr_list }.is_null());
             let attributes = mode.entry_attributes(line);
-            unsafe { *attr_list = ManuallyDrop::new(attributes).list };
+            unsafe { *attr_list = attributes.into_raw() };
         }
 
         if get_entry == 0 {
@@ -440,7 +773,7 @@ unsafe extern "C" fn token_match<T: GivesMode>(
     tokens: *mut *mut ffi::RofiIntMatcher,
     index: c_uint,
 ) -> c_int {
-    let mode: &ModeOf<'_, T> = unsafe { &*ffi::mode_get_private_data(sw).cast() };
+    let mode: &ModeOf<'_, T> = unsafe { &(*private_data::<T>(sw)).mode };
     catch_panic(|| {
         let matcher = unsafe { Matcher::from_ffi(tokens) };
         mode.matches(index as usize, matcher)
@@ -454,7 +787,7 @@ unsafe extern "C" fn get_icon<T: GivesMode>(
     selected_line: c_uint,
     height: c_int,
 ) -> *mut cairo_sys::cairo_surface_t {
-    let mode: &mut ModeOf<'_, T> = unsafe { &mut *ffi::mode_get_private_data(sw).cast() };
+    let mode: &mut ModeOf<'_, T> = unsafe { &mut (*private_data::<T>(sw)).mode };
     catch_panic(|| {
         const NEGATIVE_HEIGHT: &str = "negative height passed into get_icon";
 
@@ -472,7 +805,7 @@ unsafe extern "C" fn get_completion<T: GivesMode>(
     sw: *const ffi::Mode,
     selected_line: c_uint,
 ) -> *mut c_char {
-    let mode: &ModeOf<'_, T> = unsafe { &mut *ffi::mode_get_private_data(sw).cast() };
+    let mode: &ModeOf<'_, T> = unsafe { &(*private_data::<T>(sw)).mode };
     abort_on_panic(|| {
         mode.completed(selected_line as usize)
             .into_raw()
@@ -484,7 +817,7 @@ unsafe extern "C" fn preprocess_input<T: GivesMode>(
     sw: *mut ffi::Mode,
     input: *const c_char,
 ) -> *mut c_char {
-    let mode: &mut ModeOf<'_, T> = unsafe { &mut *ffi::mode_get_private_data(sw).cast() };
+    let mode: &mut ModeOf<'_, T> = unsafe { &mut (*private_data::<T>(sw)).mode };
     abort_on_panic(|| {
         let input = unsafe { CStr::from_ptr(input) }
             .to_str()
@@ -499,9 +832,9 @@ unsafe extern "C" fn preprocess_input<T: GivesMode>(
 }
 
 unsafe extern "C" fn get_message<T: GivesMode>(sw: *const ffi::Mode) -> *mut c_char {
-    let mode: &mut ModeOf<'_, T> = unsafe { &mut *ffi::mode_get_private_data(sw).cast() };
+    let mode: &mut ModeOf<'_, T> = unsafe { &mut (*private_data::<T>(sw)).mode };
     catch_panic(|| {
-        let message = mode.message();
+        let message = notification::decorate(sw, mode.message());
         if message.is_empty() {
             return ptr::null_mut();
         }
@@ -614,6 +947,15 @@ pub enum Action {
     ///
     /// The index must be < 1000.
     SetMode(u16),
+    /// Change the active mode to the one with the given name,
+    /// such as `window` or `drun`.
+    ///
+    /// The mode's index is looked up with [`Api::mode_index`]
+    /// when the action is taken.
+    /// If there is no active mode with this name,
+    /// the current mode is silently reloaded instead,
+    /// as with [`Action::Reload`].
+    SetModeByName(&'static str),
     /// Switch to the next mode.
     Next,
     /// Switch to the previous mode.
@@ -628,7 +970,7 @@ pub enum Action {
 
 bitflags! {
     /// The style of a text entry in the list.
-    #[derive(Default)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
     pub struct Style: u32 {
         /// The normal style.
         const NORMAL = 0;
@@ -650,71 +992,6 @@ bitflags! {
     }
 }
 
-/// A collection of attributes that can be applied to text.
-#[derive(Debug)]
-pub struct Attributes {
-    list: *mut glib_sys::GList,
-}
-
-unsafe impl Send for Attributes {}
-unsafe impl Sync for Attributes {}
-
-impl Attributes {
-    /// Create a new empty collection of attributes.
-    #[must_use]
-    pub const fn new() -> Self {
-        Self {
-            list: ptr::null_mut(),
-        }
-    }
-
-    /// An an attribute to the list.
-    pub fn push<A: Into<pango::Attribute>>(&mut self, attribute: A) {
-        let attribute: pango::Attribute = attribute.into();
-        // Convert the attribute into its raw form without copying.
-        let raw: *mut pango_sys::PangoAttribute = ManuallyDrop::new(attribute).to_glib_none_mut().0;
-        self.list = unsafe { glib_sys::g_list_prepend(self.list, raw.cast()) };
-    }
-}
-
-impl Default for Attributes {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl From<pango::Attribute> for Attributes {
-    fn from(attribute: pango::Attribute) -> Self {
-        let mut this = Self::new();
-        this.push(attribute);
-        this
-    }
-}
-
-impl Drop for Attributes {
-    fn drop(&mut self) {
-        unsafe extern "C" fn free_attribute(ptr: *mut c_void) {
-            unsafe { pango_sys::pango_attribute_destroy(ptr.cast()) }
-        }
-
-        unsafe { glib_sys::g_list_free_full(self.list, Some(free_attribute)) };
-    }
-}
-
-impl<A: Into<pango::Attribute>> Extend<A> for Attributes {
-    fn extend<T: IntoIterator<Item = A>>(&mut self, iter: T) {
-        iter.into_iter().for_each(|item| self.push(item));
-    }
-}
-
-impl<A: Into<pango::Attribute>> FromIterator<A> for Attributes {
-    fn from_iter<T: IntoIterator<Item = A>>(iter: T) -> Self {
-        let mut this = Self::new();
-        this.extend(iter);
-        this
-    }
-}
-
 /// A pattern matcher.
 #[derive(Debug, Clone, Copy)]
 pub struct Matcher<'a> {
@@ -755,13 +1032,57 @@ impl Matcher<'_> {
         };
         0 != unsafe { ffi::helper::token_match(ptr, s.as_ptr()) }
     }
+
+    /// Check whether this matcher matches any of the given strings,
+    /// in the same way Rofi's script mode matches an entry and its metadata:
+    /// each token must be found in at least one of the strings,
+    /// and negated tokens must be found in none of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the strings contain null bytes.
+    #[must_use]
+    pub fn matches_any(self, strings: &[&str]) -> bool {
+        let strings = strings
+            .iter()
+            .map(|&s| CString::new(s).expect("string contains null bytes"))
+            .collect::<Vec<_>>();
+        self.matches_any_c_str(&strings)
+    }
+
+    /// Check whether this matcher matches any of the given C strings.
+    ///
+    /// See [`Self::matches_any`] for more.
+    #[must_use]
+    pub fn matches_any_c_str<S: AsRef<CStr>>(self, strings: &[S]) -> bool {
+        let mut tokens: *const *mut ffi::RofiIntMatcher = match self.ptr {
+            Some(ptr) => ptr,
+            None => return true,
+        };
+        loop {
+            let token = unsafe { *tokens };
+            if token.is_null() {
+                return true;
+            }
+            // Rofi applies the inversion itself, so undo it to find out whether the regex matched.
+            let invert = unsafe { (*token).invert } != 0;
+            let single = [token, ptr::null_mut()];
+            let found = strings.iter().any(|s| {
+                let matched =
+                    unsafe { ffi::helper::token_match(single.as_ptr(), s.as_ref().as_ptr()) };
+                (matched != 0) != invert
+            });
+            if found == invert {
+                return false;
+            }
+            tokens = unsafe { tokens.add(1) };
+        }
+    }
 }
 
 use bitflags::bitflags;
 use cairo::ffi as cairo_sys;
-use pango::ffi as pango_sys;
 use pango::glib::ffi as glib_sys;
-use pango::glib::translate::ToGlibPtrMut;
 use std::ffi::c_void;
 use std::ffi::CStr;
 use std::ffi::CString;
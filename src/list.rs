@@ -0,0 +1,1496 @@
+//! A higher-level mode built on top of an owned list of entries.
+//!
+//! Most modes display a list of items stored in a vector,
+//! and implementing [`Mode`] for them involves
+//! writing the same indexing code in every method.
+//! A [`ListMode`] instead only deals with what happens when the user interacts with the list,
+//! and the entries themselves are stored as [`Entry`] values
+//! inside the [`Context`] which this module manages.
+//!
+//! To export a list mode,
+//! wrap it in a [`List`] when passing it to [`export_mode!`]:
+//!
+//! ```no_run
+//! use rofi_mode::list;
+//!
+//! struct Mode;
+//!
+//! impl rofi_mode::ListMode<'_> for Mode {
+//!     const NAME: &'static str = "an-example-list-mode\0";
+//!     type Data = u32;
+//!
+//!     fn init(cx: &mut list::Context<'_, u32>) -> Result<Self, ()> {
+//!         cx.entries.push(list::Entry::new("one", 1));
+//!         cx.entries.push(list::Entry::new("two", 2).with_meta("second"));
+//!         Ok(Self)
+//!     }
+//!
+//!     fn select(
+//!         &mut self,
+//!         cx: &mut list::Context<'_, u32>,
+//!         selected: usize,
+//!         _alt: bool,
+//!         _input: &mut rofi_mode::String,
+//!     ) -> rofi_mode::Action {
+//!         println!("{}", cx.entries[selected].data);
+//!         rofi_mode::Action::Exit
+//!     }
+//! }
+//!
+//! rofi_mode::export_mode!(rofi_mode::List<'_, Mode>);
+//! ```
+//!
+//! A list can also contain nested menus:
+//! calling [`Context::push`] when an entry is selected opens a child [`Menu`]
+//! with its own entries,
+//! and cancelling returns to the parent menu instead of exiting.
+//!
+//! Similarly, [`Context::confirm`] asks the user a yes or no question
+//! and [`Context::run_wizard`] collects several values through a [`Wizard`],
+//! both temporarily taking the place of the list.
+//!
+//! When the list is reloaded after an event,
+//! the entry that was selected stays selected even if it moved,
+//! unless the mode requests a different line with [`Api::select_line_after_reload`].
+//!
+//! [`export_mode!`]: crate::export_mode
+
+/// A mode whose entries are stored in a list managed by this library.
+///
+/// Types implementing this trait can be turned into a [`Mode`]
+/// by wrapping them in a [`List`].
+pub trait ListMode<'rofi>: Sized + Sync {
+    /// The name of the mode.
+    ///
+    /// This has the same requirements as [`Mode::NAME`].
+    const NAME: &'static str;
+
+    /// The icon shown next to entries without their own, as described in [`Mode::ICON`].
+    const ICON: Option<&'static str> = None;
+
+    /// The user data stored alongside each entry.
+    type Data: Sync;
+
+    /// Initialize the mode,
+    /// usually filling in the initial list of entries.
+    ///
+    /// # Errors
+    ///
+    /// This function is allowed to error,
+    /// in which case Rofi will display a message
+    /// as described in [`Mode::init`].
+    #[allow(clippy::result_unit_err)]
+    fn init(cx: &mut Context<'rofi, Self::Data>) -> Result<Self, ()>;
+
+    /// Handle the mode becoming active again,
+    /// as described in [`Mode::reactivated`].
+    ///
+    /// The default implementation does nothing.
+    fn reactivated(&mut self, _cx: &mut Context<'rofi, Self::Data>) {}
+
+    /// Tear down the mode when Rofi exits,
+    /// as described in [`Mode::destroy`].
+    ///
+    /// The default implementation does nothing.
+    fn destroy(self, _cx: &mut Context<'rofi, Self::Data>, _action: Option<Action>) {}
+
+    /// Handle the user accepting an entry from the list
+    /// (an [`Event::Ok`]),
+    /// returning the next action to be taken.
+    ///
+    /// `selected` is the index of the entry in [`Context::entries`],
+    /// and `alt` is whether the alt binding was used.
+    fn select(
+        &mut self,
+        cx: &mut Context<'rofi, Self::Data>,
+        selected: usize,
+        alt: bool,
+        input: &mut String,
+    ) -> Action;
+
+    /// Handle the user accepting while several entries are [selected](crate::selection),
+    /// returning the next action to be taken.
+    ///
+    /// `selected` contains the indices of the selected entries in [`Context::entries`],
+    /// in the order they are displayed,
+    /// and `alt` is whether the alt binding was used.
+    ///
+    /// The default implementation calls [`Self::select`] with each entry in turn,
+    /// returning the action returned by the last call.
+    /// Modes whose `select` changes the list of entries should override this.
+    fn select_many(
+        &mut self,
+        cx: &mut Context<'rofi, Self::Data>,
+        selected: &[usize],
+        alt: bool,
+        input: &mut String,
+    ) -> Action {
+        let mut action = Action::Reload;
+        for &line in selected {
+            action = self.select(cx, line, alt, input);
+        }
+        action
+    }
+
+    /// Handle any event other than [`Event::Ok`],
+    /// such as custom input or custom commands,
+    /// returning the next action to be taken.
+    ///
+    /// The default implementation exits on [`Event::Cancel`],
+    /// fills in the input with the selected entry's text on [`Event::Complete`],
+    /// and otherwise ignores the event and reloads.
+    fn react(
+        &mut self,
+        cx: &mut Context<'rofi, Self::Data>,
+        event: Event,
+        input: &mut String,
+    ) -> Action {
+        match event {
+            Event::Cancel { .. } => return Action::Exit,
+            Event::Complete {
+                selected: Some(selected),
+            } => *input = cx.entries[selected].completed(),
+            _ => {}
+        }
+        Action::Reload
+    }
+
+    /// Get the message to show in the message bar.
+    ///
+    /// This has the same requirements as [`Mode::message`].
+    ///
+    /// The default implementation returns an empty string.
+    fn message(&mut self, _cx: &mut Context<'rofi, Self::Data>) -> String {
+        String::new()
+    }
+}
+
+/// The state of a [`List`] that is shared with its [`ListMode`].
+#[derive(Debug)]
+pub struct Context<'rofi, T> {
+    /// The Rofi API.
+    pub api: Api<'rofi>,
+
+    /// The entries of the list, in the order they are displayed.
+    pub entries: Vec<Entry<T>>,
+
+    /// The selection history of the list, if it has one.
+    ///
+    /// When this is set, every [`Event::Ok`] is recorded in it,
+    /// and [`Self::entries`] are kept sorted by [frecency](crate::history)
+    /// after [`ListMode::init`] and whenever the mode reacts to an event or is reactivated.
+    #[cfg(feature = "state")]
+    pub history: Option<History>,
+
+    /// The pinned entries of the list, if it supports pinning.
+    ///
+    /// When this is set, pinned entries are displayed with [`Style::ACTIVE`]
+    /// and [`Self::entries`] are kept sorted with the pinned entries first,
+    /// after sorting by [`Self::history`].
+    /// The [pin commands](crate::pins) are handled by the list
+    /// and are not passed to [`ListMode::react`].
+    #[cfg(feature = "state")]
+    pub pins: Option<Pins>,
+
+    /// The entries the user has selected, if the list supports selecting several entries.
+    ///
+    /// When this is set, selected entries are displayed with the selection's style,
+    /// the number of selected entries is shown in the message bar,
+    /// and accepting while entries are selected calls [`ListMode::select_many`].
+    /// The [toggle command](crate::selection) is handled by the list
+    /// and is not passed to [`ListMode::react`].
+    pub selection: Option<Selection>,
+
+    /// The parents of the current menu, outermost first.
+    stack: Vec<Parent<T>>,
+    /// The display name of the root menu, saved when the first child menu is entered.
+    root_display_name: Option<String>,
+    /// The message of the current menu, if it is a child menu with its own message.
+    menu_message: Option<String>,
+    /// The input and selected line at the time of the event being handled.
+    input: String,
+    selected: Option<usize>,
+    /// The input to replace the user's input with once the event has been handled.
+    new_input: Option<String>,
+    /// The question being asked with [`Self::confirm`], if there is one.
+    confirmation: Option<Confirmation<'rofi, T>>,
+    /// The wizard started with [`Self::run_wizard`], if there is one.
+    wizard: Option<RunningWizard<'rofi, T>>,
+}
+
+/// A child menu that can be entered with [`Context::push`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Menu<T> {
+    /// The title of the menu, shown in the breadcrumb of the display name.
+    pub title: String,
+
+    /// The entries of the menu.
+    pub entries: Vec<Entry<T>>,
+
+    /// The message shown in the message bar while the menu is open.
+    ///
+    /// If this is [`None`], [`ListMode::message`] is used.
+    pub message: Option<String>,
+}
+
+impl<T> Menu<T> {
+    /// Create a new menu with the given title and entries.
+    #[must_use]
+    pub fn new<S: Into<String>>(title: S, entries: Vec<Entry<T>>) -> Self {
+        Self {
+            title: title.into(),
+            entries,
+            message: None,
+        }
+    }
+
+    /// Set the message of the menu.
+    #[must_use]
+    pub fn with_message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// A question asked with [`Context::confirm`].
+struct Confirmation<'rofi, T> {
+    question: Markup,
+    /// The choices answering yes and no respectively.
+    choices: [String; 2],
+    on_answer: AnswerFn<'rofi, T>,
+    input: String,
+    selected: Option<usize>,
+}
+
+type AnswerFn<'rofi, T> = Box<dyn FnOnce(&mut Context<'rofi, T>, bool) -> Action + Sync>;
+
+impl<T> Debug for Confirmation<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Confirmation")
+            .field("question", &self.question)
+            .field("choices", &self.choices)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Context<'_, T> {
+    /// Ask the user a yes or no question,
+    /// temporarily replacing the list with the choices "Yes" and "No"
+    /// and showing the question in the message bar.
+    /// The question is escaped unless it is [`Markup`].
+    ///
+    /// Once the user picks a choice or cancels,
+    /// the list, input and selection are restored
+    /// and `on_answer` is called with whether the user answered yes.
+    /// The action it returns is then taken.
+    ///
+    /// This should be called from [`ListMode::select`] or [`ListMode::react`],
+    /// which should then return [`Action::Reload`].
+    ///
+    /// ```
+    /// # use rofi_mode::{list::Context, Action, Event};
+    /// fn react(cx: &mut Context<'_, ()>, event: Event) -> Action {
+    ///     if let Event::DeleteEntry { selected } = event {
+    ///         let question = format!("Delete {}?", cx.entries[selected].text);
+    ///         cx.confirm(question, move |cx, yes| {
+    ///             if yes {
+    ///                 cx.entries.remove(selected);
+    ///             }
+    ///             Action::Reload
+    ///         });
+    ///     }
+    ///     Action::Reload
+    /// }
+    /// ```
+    pub fn confirm<Q, F>(&mut self, question: Q, on_answer: F)
+    where
+        Q: Into<Markup>,
+        F: FnOnce(&mut Self, bool) -> Action + Sync + 'static,
+    {
+        self.confirm_with_choices(question, "Yes", "No", on_answer);
+    }
+
+    /// Ask the user a yes or no question with custom labels for the two choices,
+    /// as in [`Self::confirm`].
+    pub fn confirm_with_choices<Q, Y, N, F>(&mut self, question: Q, yes: Y, no: N, on_answer: F)
+    where
+        Q: Into<Markup>,
+        Y: Into<String>,
+        N: Into<String>,
+        F: FnOnce(&mut Self, bool) -> Action + Sync + 'static,
+    {
+        self.confirmation = Some(Confirmation {
+            question: question.into(),
+            choices: [yes.into(), no.into()],
+            on_answer: Box::new(on_answer),
+            input: self.input.clone(),
+            selected: self.selected,
+        });
+        self.new_input = Some(String::new());
+        self.api.select_line_after_reload(0);
+    }
+
+    /// Whether a question asked with [`Self::confirm`] is waiting for an answer.
+    #[must_use]
+    pub fn is_confirming(&self) -> bool {
+        self.confirmation.is_some()
+    }
+}
+
+/// A wizard started with [`Context::run_wizard`].
+struct RunningWizard<'rofi, T> {
+    runner: Box<dyn WizardRunner<'rofi, T> + Sync>,
+    display_name: Option<String>,
+    input: String,
+    selected: Option<usize>,
+}
+
+impl<T> Debug for RunningWizard<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RunningWizard")
+            .field("prompt", &self.runner.prompt())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`Wizard`] with the type of its value erased.
+trait WizardRunner<'rofi, T> {
+    fn prompt(&self) -> &str;
+    fn message(&self) -> Option<&Markup>;
+    fn suggestions(&self) -> &[String];
+    fn answer(&mut self, input: &str) -> Progress;
+    fn back(&mut self) -> Option<String>;
+    /// Call the callback with the wizard's value if it was completed,
+    /// or [`None`] if it was aborted.
+    fn finish(self: Box<Self>, cx: &mut Context<'rofi, T>, completed: bool) -> Action;
+}
+
+struct WizardCallback<S, F> {
+    wizard: Wizard<S>,
+    on_finish: F,
+}
+
+impl<'rofi, T, S, F> WizardRunner<'rofi, T> for WizardCallback<S, F>
+where
+    F: FnOnce(&mut Context<'rofi, T>, Option<S>) -> Action,
+{
+    fn prompt(&self) -> &str {
+        self.wizard.prompt()
+    }
+    fn message(&self) -> Option<&Markup> {
+        self.wizard.message()
+    }
+    fn suggestions(&self) -> &[String] {
+        self.wizard.suggestions()
+    }
+    fn answer(&mut self, input: &str) -> Progress {
+        self.wizard.answer(input)
+    }
+    fn back(&mut self) -> Option<String> {
+        self.wizard.back()
+    }
+    fn finish(self: Box<Self>, cx: &mut Context<'rofi, T>, completed: bool) -> Action {
+        let value = if completed {
+            Some(self.wizard.into_value())
+        } else {
+            None
+        };
+        (self.on_finish)(cx, value)
+    }
+}
+
+impl<T> Context<'_, T> {
+    /// Start a [`Wizard`],
+    /// which collects several values from the user one step at a time.
+    /// See [the `wizard` module](crate::wizard) for more.
+    ///
+    /// Once the wizard is completed or aborted,
+    /// the list, input, selection and display name are restored
+    /// and `on_finish` is called with the wizard's value,
+    /// or [`None`] if the user cancelled its first step.
+    /// The action it returns is then taken.
+    ///
+    /// This should be called from [`ListMode::select`] or [`ListMode::react`],
+    /// which should then return [`Action::Reload`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the wizard has no steps.
+    pub fn run_wizard<S, F>(&mut self, wizard: Wizard<S>, on_finish: F)
+    where
+        S: Sync + 'static,
+        F: FnOnce(&mut Self, Option<S>) -> Action + Sync + 'static,
+    {
+        assert!(wizard.steps() > 0, "a wizard must have at least one step");
+        let display_name = self.api.replace_display_name(wizard.prompt().into());
+        self.wizard = Some(RunningWizard {
+            runner: Box::new(WizardCallback { wizard, on_finish }),
+            display_name,
+            input: self.input.clone(),
+            selected: self.selected,
+        });
+        self.new_input = Some(String::new());
+        self.api.select_line_after_reload(0);
+    }
+
+    /// Whether a wizard started with [`Self::run_wizard`] is running.
+    #[must_use]
+    pub fn is_running_wizard(&self) -> bool {
+        self.wizard.is_some()
+    }
+
+    /// Get the lines displayed instead of the entries
+    /// while a question is being asked or a wizard is running.
+    fn prompt_lines(&self) -> Option<&[String]> {
+        if let Some(confirmation) = &self.confirmation {
+            return Some(&confirmation.choices);
+        }
+        Some(self.wizard.as_ref()?.runner.suggestions())
+    }
+
+    /// Get the prompt line displayed on the given line, if there is one.
+    fn prompt_line(&self, line: usize) -> Option<&String> {
+        Some(&self.prompt_lines()?[line])
+    }
+
+    /// Get the identity of the entry on the given line,
+    /// unless a prompt is displayed instead of the entries.
+    fn selected_identity(&self, line: Option<usize>) -> Option<(usize, std::string::String)> {
+        if self.prompt_lines().is_some() {
+            return None;
+        }
+        let line = line?;
+        Some((line, self.entries.get(line)?.identity().into()))
+    }
+
+    /// Keep the entry with the given identity selected after the list is reloaded,
+    /// if it moved and the mode hasn't requested a different line be selected.
+    fn keep_selection(&mut self, line: usize, id: &str) {
+        if self.api.has_pending_selection() || self.prompt_lines().is_some() {
+            return;
+        }
+        let new_line = self
+            .entries
+            .iter()
+            .position(|entry| entry.identity().as_str() == id);
+        if let Some(new_line) = new_line {
+            if new_line != line {
+                self.api.select_line_after_reload(new_line);
+            }
+        }
+    }
+
+    /// Restore the input and selection saved when a prompt was started.
+    fn restore(&mut self, input: String, selected: Option<usize>) {
+        if let Some(selected) = selected {
+            self.api.select_line_after_reload(selected);
+        }
+        self.input = input.clone();
+        self.selected = selected;
+        self.new_input = Some(input);
+    }
+}
+
+/// The saved state of a menu that has a child menu open.
+#[derive(Debug)]
+struct Parent<T> {
+    title: String,
+    entries: Vec<Entry<T>>,
+    message: Option<String>,
+    input: String,
+    selected: Option<usize>,
+}
+
+impl<T> Context<'_, T> {
+    /// Enter a child menu,
+    /// replacing [`Self::entries`] with the menu's entries.
+    ///
+    /// The current entries are saved along with the user's input and selection,
+    /// and restored when the child menu is left with [`Self::pop`]
+    /// or by the user cancelling.
+    /// The display name is changed to a breadcrumb of the titles of all open menus.
+    ///
+    /// This should be called from [`ListMode::select`] or [`ListMode::react`],
+    /// which should then return [`Action::Reload`].
+    pub fn push(&mut self, menu: Menu<T>) {
+        if self.stack.is_empty() {
+            self.root_display_name = match self.api.display_name() {
+                Some(display_name) => Some(display_name.into()),
+                None => Some((&*self.api.mode_name()).into()),
+            };
+        }
+        self.stack.push(Parent {
+            title: menu.title,
+            entries: mem::replace(&mut self.entries, menu.entries),
+            message: mem::replace(&mut self.menu_message, menu.message),
+            input: self.input.clone(),
+            selected: self.selected,
+        });
+        self.new_input = Some(String::new());
+        self.api.select_line_after_reload(0);
+        self.update_display_name();
+    }
+
+    /// Leave the current child menu,
+    /// restoring the entries, input and selection of its parent.
+    ///
+    /// Returns `false` if there was no child menu open.
+    pub fn pop(&mut self) -> bool {
+        let parent = match self.stack.pop() {
+            Some(parent) => parent,
+            None => return false,
+        };
+        self.entries = parent.entries;
+        self.menu_message = parent.message;
+        self.new_input = Some(parent.input);
+        if let Some(selected) = parent.selected {
+            self.api.select_line_after_reload(selected);
+        }
+        self.update_display_name();
+        true
+    }
+
+    /// Get the number of child menus currently open.
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Get the titles of the open child menus, outermost first.
+    pub fn path(&self) -> impl Iterator<Item = &str> + '_ {
+        self.stack.iter().map(|parent| parent.title.as_str())
+    }
+
+    fn update_display_name(&mut self) {
+        if self.stack.is_empty() {
+            match self.root_display_name.take() {
+                Some(display_name) => drop(self.api.replace_display_name(display_name)),
+                None => drop(self.api.take_display_name()),
+            }
+            return;
+        }
+        let mut breadcrumb = self.root_display_name.clone().unwrap_or_default();
+        for title in self.path() {
+            breadcrumb.push_str(" / ");
+            breadcrumb.push_str(title);
+        }
+        self.api.replace_display_name(breadcrumb);
+    }
+}
+
+/// An entry in a [`ListMode`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Entry<T> {
+    /// The text content of the entry.
+    pub text: String,
+
+    /// A stable identifier of the entry,
+    /// used to tell it apart from other entries across runs of the mode.
+    ///
+    /// If this is [`None`], the entry's text with any markup removed is used.
+    /// See [`Self::identity`].
+    pub id: Option<String>,
+
+    /// Whether [`Self::text`] contains [Pango markup].
+    ///
+    /// [Pango markup]: https://docs.gtk.org/Pango/pango_markup.html
+    pub markup: bool,
+
+    /// The text style of the entry.
+    ///
+    /// [`Style::MARKUP`] is added automatically if [`Self::markup`] is set.
+    pub style: Style,
+
+    /// The text attributes associated with the entry.
+    pub attributes: Attributes,
+
+    /// The name of the entry's icon in the icon theme, or a path to it,
+    /// if it has one.
+    pub icon: Option<String>,
+
+    /// Hidden metadata used when matching against the entry,
+    /// as described in [`Mode::entry_meta`].
+    pub meta: String,
+
+    /// The text the user's input is matched against,
+    /// along with [`Self::meta`].
+    ///
+    /// If this is [`None`], the entry's text with any markup removed is used.
+    pub search: Option<String>,
+
+    /// The value the input is set to when the entry is completed,
+    /// as described in [`Mode::completed`].
+    ///
+    /// If this is [`None`], the entry's text with any markup removed is used.
+    pub completion: Option<String>,
+
+    /// Arbitrary user data associated with the entry.
+    pub data: T,
+
+    /// The plain text of the entry, saved so that markup is not parsed on every render.
+    plain_text: PlainTextCache,
+}
+
+impl<T> Entry<T> {
+    /// Create a new entry with the given text content and user data.
+    #[must_use]
+    pub fn new<S: Into<String>>(text: S, data: T) -> Self {
+        Self {
+            text: text.into(),
+            id: None,
+            markup: false,
+            style: Style::NORMAL,
+            attributes: Attributes::new(),
+            icon: None,
+            meta: String::new(),
+            search: None,
+            completion: None,
+            data,
+            plain_text: PlainTextCache::default(),
+        }
+    }
+
+    /// Set the stable identifier of the entry.
+    #[must_use]
+    pub fn with_id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Set whether the entry's text contains Pango markup.
+    #[must_use]
+    pub fn with_markup(mut self, markup: bool) -> Self {
+        self.markup = markup;
+        self
+    }
+
+    /// Set the text style of the entry.
+    #[must_use]
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the text attributes of the entry.
+    #[must_use]
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Set the icon of the entry.
+    #[must_use]
+    pub fn with_icon<S: Into<String>>(mut self, icon: S) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Set the hidden metadata of the entry.
+    #[must_use]
+    pub fn with_meta<S: Into<String>>(mut self, meta: S) -> Self {
+        self.meta = meta.into();
+        self
+    }
+
+    /// Set the text the user's input is matched against.
+    #[must_use]
+    pub fn with_search<S: Into<String>>(mut self, search: S) -> Self {
+        self.search = Some(search.into());
+        self
+    }
+
+    /// Set the completed value of the entry.
+    #[must_use]
+    pub fn with_completion<S: Into<String>>(mut self, completion: S) -> Self {
+        self.completion = Some(completion.into());
+        self
+    }
+
+    /// Get the [completed value](Mode::completed) of the entry:
+    /// [`Self::completion`] if it is set,
+    /// and otherwise the [plain text](Self::plain_text).
+    #[must_use]
+    pub fn completed(&self) -> String {
+        match &self.completion {
+            Some(completion) => completion.clone(),
+            None => self.plain_text(),
+        }
+    }
+
+    /// Get the identity of the entry:
+    /// [`Self::id`] if it is set,
+    /// and otherwise the [plain text](Self::plain_text).
+    #[must_use]
+    pub fn identity(&self) -> String {
+        match &self.id {
+            Some(id) => id.clone(),
+            None => self.plain_text(),
+        }
+    }
+
+    /// Get the text of the entry with any markup removed.
+    ///
+    /// This is the text matched against the user's input
+    /// unless [`Self::search`] is set.
+    #[must_use]
+    pub fn plain_text(&self) -> String {
+        self.with_plain_text(String::clone)
+    }
+
+    /// Call a function with the plain text of the entry, without copying it.
+    fn with_plain_text<R, F: FnOnce(&String) -> R>(&self, f: F) -> R {
+        if !self.markup {
+            return f(&self.text);
+        }
+        let parse = || match pango::parse_markup(&self.text, '\0') {
+            Ok((_, text, _)) => text.into(),
+            Err(_) => self.text.clone(),
+        };
+        self.plain_text.with(&self.text, parse, f)
+    }
+}
+
+/// The plain text of a markup entry along with the markup it was parsed from,
+/// so that it is only parsed again when the entry's text changes.
+#[derive(Default)]
+struct PlainTextCache(Mutex<Option<(String, String)>>);
+
+impl PlainTextCache {
+    /// Call `f` with the plain text of `markup`,
+    /// parsing it with `parse` if it isn't cached.
+    fn with<P, F, R>(&self, markup: &String, parse: P, f: F) -> R
+    where
+        P: FnOnce() -> String,
+        F: FnOnce(&String) -> R,
+    {
+        let mut cache = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let (_, plain_text) = match &mut *cache {
+            Some(cached) if cached.0 == *markup => cached,
+            cache => cache.insert((markup.clone(), parse())),
+        };
+        f(plain_text)
+    }
+}
+
+impl Clone for PlainTextCache {
+    fn clone(&self) -> Self {
+        let cache = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        Self(Mutex::new(cache.clone()))
+    }
+}
+
+impl Debug for PlainTextCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlainTextCache").finish_non_exhaustive()
+    }
+}
+
+impl From<&str> for Entry<()> {
+    fn from(text: &str) -> Self {
+        Self::new(text, ())
+    }
+}
+
+impl From<std::string::String> for Entry<()> {
+    fn from(text: std::string::String) -> Self {
+        Self::new(text, ())
+    }
+}
+
+impl From<String> for Entry<()> {
+    fn from(text: String) -> Self {
+        Self::new(text, ())
+    }
+}
+
+impl From<Markup> for Entry<()> {
+    fn from(markup: Markup) -> Self {
+        Self::new(markup, ()).with_markup(true)
+    }
+}
+
+/// A type that can describe how it is displayed as an [`Entry`] in a list.
+///
+/// With the `derive` feature enabled,
+/// this can be implemented with `#[derive(rofi_mode::Entry)]`.
+pub trait IntoEntry: Sized {
+    /// Convert this value into an entry,
+    /// storing itself as the entry's [data](Entry::data).
+    fn into_entry(self) -> Entry<Self>;
+}
+
+#[cfg(feature = "state")]
+impl<T> Context<'_, T> {
+    /// Record the selection of an entry in the history, if there is one.
+    fn record(&mut self, selected: usize) {
+        if let Some(history) = &mut self.history {
+            history.record(self.entries[selected].identity().as_str());
+        }
+    }
+
+    /// Handle a custom command if it is one of the pin commands,
+    /// returning whether it was handled.
+    fn pin_command(&mut self, number: u8, selected: Option<usize>) -> bool {
+        match (&mut self.pins, selected) {
+            (Some(pins), Some(selected)) => {
+                pins.handle_command(number, self.entries[selected].identity().as_str())
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the given entry is pinned.
+    fn is_pinned(&self, line: usize) -> bool {
+        match &self.pins {
+            Some(pins) => pins.is_pinned(self.entries[line].identity().as_str()),
+            None => false,
+        }
+    }
+
+    /// Sort the entries by the history and pins, if there are any.
+    fn sort(&mut self) {
+        if let Some(history) = &self.history {
+            history.sort(&mut self.entries);
+        }
+        if let Some(pins) = &self.pins {
+            pins.sort(&mut self.entries);
+        }
+    }
+}
+
+impl<T> Context<'_, T> {
+    /// Toggle the selected entry if the custom command is the selection's toggle command,
+    /// returning whether it was.
+    fn toggle_selection(&mut self, number: u8, selected: Option<usize>) -> bool {
+        match (&mut self.selection, selected) {
+            (Some(selection), Some(selected)) if selection.toggle_command() == number => {
+                selection.toggle(self.entries[selected].identity().as_str());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Clear the selection,
+    /// returning the lines of the entries that were selected
+    /// if there were any.
+    fn take_selection(&mut self) -> Option<Vec<usize>> {
+        let selection = self.selection.as_mut()?;
+        let lines = selection.lines(&self.entries);
+        selection.clear();
+        if lines.is_empty() {
+            return None;
+        }
+        Some(lines)
+    }
+}
+
+impl<T: IntoEntry> Context<'_, T> {
+    /// Replace the list of entries with the given values.
+    pub fn set_entries<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        self.entries.clear();
+        self.entries
+            .extend(values.into_iter().map(IntoEntry::into_entry));
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub trait Icon {
+        fn icon(&self) -> Option<String>;
+    }
+
+    impl<T: ?Sized + Icon> Icon for &T {
+        fn icon(&self) -> Option<String> {
+            (**self).icon()
+        }
+    }
+
+    impl<T: Icon> Icon for Option<T> {
+        fn icon(&self) -> Option<String> {
+            self.as_ref()?.icon()
+        }
+    }
+
+    impl Icon for str {
+        fn icon(&self) -> Option<String> {
+            Some(self.into())
+        }
+    }
+
+    impl Icon for String {
+        fn icon(&self) -> Option<String> {
+            Some(self.clone())
+        }
+    }
+
+    impl Icon for std::string::String {
+        fn icon(&self) -> Option<String> {
+            Some(self.into())
+        }
+    }
+
+    impl Icon for Path {
+        fn icon(&self) -> Option<String> {
+            Some(self.to_string_lossy().into())
+        }
+    }
+
+    impl Icon for PathBuf {
+        fn icon(&self) -> Option<String> {
+            Some(self.to_string_lossy().into())
+        }
+    }
+
+    pub fn push_meta<T: ?Sized + Display>(meta: &mut String, value: &T) {
+        if !meta.is_empty() {
+            meta.push(' ');
+        }
+        write!(meta, "{value}").unwrap();
+    }
+
+    use crate::String;
+    use std::fmt::Display;
+    use std::fmt::Write as _;
+    use std::path::Path;
+    use std::path::PathBuf;
+}
+
+/// An adapter turning a [`ListMode`] into a [`Mode`].
+///
+/// This is the type that should be passed to [`export_mode!`](crate::export_mode).
+#[derive(Debug)]
+pub struct List<'rofi, M: ListMode<'rofi>> {
+    mode: M,
+    cx: Context<'rofi, M::Data>,
+}
+
+impl<'rofi, M: ListMode<'rofi>> Mode<'rofi> for List<'rofi, M> {
+    const NAME: &'static str = M::NAME;
+    const ICON: Option<&'static str> = M::ICON;
+
+    fn init(api: Api<'rofi>) -> Result<Self, ()> {
+        let mut cx = Context {
+            api,
+            entries: Vec::new(),
+            #[cfg(feature = "state")]
+            history: None,
+            #[cfg(feature = "state")]
+            pins: None,
+            selection: None,
+            stack: Vec::new(),
+            root_display_name: None,
+            menu_message: None,
+            input: String::new(),
+            selected: None,
+            new_input: None,
+            confirmation: None,
+            wizard: None,
+        };
+        let mode = M::init(&mut cx)?;
+        #[cfg(feature = "state")]
+        cx.sort();
+        Ok(Self { mode, cx })
+    }
+
+    fn reactivated(&mut self) {
+        self.mode.reactivated(&mut self.cx);
+        #[cfg(feature = "state")]
+        self.cx.sort();
+    }
+
+    fn destroy(mut self, action: Option<Action>) {
+        self.mode.destroy(&mut self.cx, action);
+    }
+
+    fn entries(&mut self) -> usize {
+        match self.cx.prompt_lines() {
+            Some(lines) => lines.len(),
+            None => self.cx.entries.len(),
+        }
+    }
+
+    fn entry_content(&self, line: usize) -> String {
+        match self.cx.prompt_line(line) {
+            Some(choice) => choice.clone(),
+            None => self.cx.entries[line].text.clone(),
+        }
+    }
+
+    fn entry_style(&self, line: usize) -> Style {
+        if self.cx.prompt_lines().is_some() {
+            return Style::NORMAL;
+        }
+        let entry = &self.cx.entries[line];
+        let mut style = entry.style;
+        if entry.markup {
+            style |= Style::MARKUP;
+        }
+        if let Some(selection) = &self.cx.selection {
+            if selection.contains(entry.identity().as_str()) {
+                style |= selection.style();
+            }
+        }
+        #[cfg(feature = "state")]
+        if self.cx.is_pinned(line) {
+            style |= Style::ACTIVE;
+        }
+        style
+    }
+
+    fn entry_attributes(&self, line: usize) -> Attributes {
+        if self.cx.prompt_lines().is_some() {
+            return Attributes::new();
+        }
+        self.cx.entries[line].attributes.clone()
+    }
+
+    fn entry_icon(&mut self, line: usize, height: u32) -> Option<cairo::Surface> {
+        if self.cx.prompt_lines().is_some() {
+            return None;
+        }
+        let icon = self.cx.entries[line].icon.as_ref()?;
+        let api = &mut self.cx.api;
+        api.query_icon_cstr(icon.as_ref(), height).wait(api).ok()
+    }
+
+    fn react(&mut self, event: Event, input: &mut String) -> Action {
+        let selected = self.cx.selected_identity(event.selected());
+        let action = self.dispatch(event, input);
+        if let (Action::Reload, Some((line, id))) = (action, selected) {
+            self.cx.keep_selection(line, &id);
+        }
+        action
+    }
+
+    fn entry_meta(&self, line: usize) -> String {
+        if self.cx.prompt_lines().is_some() {
+            return String::new();
+        }
+        self.cx.entries[line].meta.clone()
+    }
+
+    fn matches(&self, line: usize, matcher: Matcher<'_>) -> bool {
+        if let Some(choice) = self.cx.prompt_line(line) {
+            return matcher.matches(choice);
+        }
+        let entry = &self.cx.entries[line];
+        match &entry.search {
+            Some(search) => matcher.matches_any_c_str(&[search, &entry.meta]),
+            None => entry.with_plain_text(|text| matcher.matches_any_c_str(&[text, &entry.meta])),
+        }
+    }
+
+    fn completed(&self, line: usize) -> String {
+        match self.cx.prompt_line(line) {
+            Some(choice) => choice.clone(),
+            None => self.cx.entries[line].completed(),
+        }
+    }
+
+    fn message(&mut self) -> String {
+        if let Some(confirmation) = &self.cx.confirmation {
+            return confirmation.question.as_str().into();
+        }
+        if let Some(running) = &self.cx.wizard {
+            return running
+                .runner
+                .message()
+                .map_or_else(String::new, |message| message.as_str().into());
+        }
+        let mut message = match &self.cx.menu_message {
+            Some(message) => message.clone(),
+            None => self.mode.message(&mut self.cx),
+        };
+        if let Some(selection) = &self.cx.selection {
+            if !selection.is_empty() {
+                if !message.is_empty() {
+                    message.push('\n');
+                }
+                message.push_str(&selection.len().to_string());
+                message.push_str(" selected");
+            }
+        }
+        message
+    }
+}
+
+impl<'rofi, M: ListMode<'rofi>> List<'rofi, M> {
+    /// Handle an event, passing it on to the mode if the list doesn't handle it.
+    fn dispatch(&mut self, event: Event, input: &mut String) -> Action {
+        if let Some(confirmation) = self.cx.confirmation.take() {
+            let yes = match event {
+                Event::Ok { selected, .. } => selected == 0,
+                Event::Cancel { .. } => false,
+                _ => {
+                    self.cx.confirmation = Some(confirmation);
+                    return Action::Reload;
+                }
+            };
+            self.cx.restore(confirmation.input, confirmation.selected);
+            let action = (confirmation.on_answer)(&mut self.cx, yes);
+            return self.finish(action, input);
+        }
+
+        if let Some(running) = self.cx.wizard.take() {
+            return self.react_wizard(running, event, input);
+        }
+
+        if let Event::Cancel { .. } = event {
+            if self.cx.pop() {
+                *input = self.cx.new_input.take().unwrap_or_default();
+                return Action::Reload;
+            }
+        }
+
+        if let Event::CustomCommand { number, selected } = event {
+            if self.cx.toggle_selection(number, selected) {
+                return Action::Reload;
+            }
+        }
+
+        #[cfg(feature = "state")]
+        if let Event::CustomCommand { number, selected } = event {
+            if self.cx.pin_command(number, selected) {
+                self.cx.sort();
+                return Action::Reload;
+            }
+        }
+
+        self.cx.input = input.clone();
+        self.cx.selected = event.selected();
+        let action = match event {
+            Event::Ok { alt, selected } => {
+                if let Some(lines) = self.cx.take_selection() {
+                    #[cfg(feature = "state")]
+                    for &line in &lines {
+                        self.cx.record(line);
+                    }
+                    self.mode.select_many(&mut self.cx, &lines, alt, input)
+                } else {
+                    #[cfg(feature = "state")]
+                    self.cx.record(selected);
+                    self.mode.select(&mut self.cx, selected, alt, input)
+                }
+            }
+            _ => self.mode.react(&mut self.cx, event, input),
+        };
+        self.finish(action, input)
+    }
+
+    /// Apply the changes requested by the mode while handling an event.
+    fn finish(&mut self, action: Action, input: &mut String) -> Action {
+        if let Some(new_input) = self.cx.new_input.take() {
+            *input = new_input;
+        }
+        #[cfg(feature = "state")]
+        if action != Action::Exit {
+            self.cx.sort();
+        }
+        action
+    }
+
+    /// Handle an event while a wizard is running.
+    fn react_wizard(
+        &mut self,
+        mut running: RunningWizard<'rofi, M::Data>,
+        event: Event,
+        input: &mut String,
+    ) -> Action {
+        let answer = match event {
+            Event::Ok { selected, .. } => running.runner.suggestions()[selected].clone(),
+            Event::CustomInput { .. } => input.clone(),
+            Event::Cancel { .. } => match running.runner.back() {
+                Some(previous) => {
+                    *input = previous;
+                    self.cx
+                        .api
+                        .replace_display_name(running.runner.prompt().into());
+                    self.cx.wizard = Some(running);
+                    return Action::Reload;
+                }
+                None => return self.finish_wizard(running, false, input),
+            },
+            Event::Complete {
+                selected: Some(selected),
+            } => {
+                *input = running.runner.suggestions()[selected].clone();
+                self.cx.wizard = Some(running);
+                return Action::Reload;
+            }
+            _ => {
+                self.cx.wizard = Some(running);
+                return Action::Reload;
+            }
+        };
+
+        match running.runner.answer(&answer) {
+            Progress::Invalid => *input = answer,
+            Progress::Next => {
+                input.clear();
+                self.cx.api.select_line_after_reload(0);
+                self.cx
+                    .api
+                    .replace_display_name(running.runner.prompt().into());
+            }
+            Progress::Finished => return self.finish_wizard(running, true, input),
+        }
+        self.cx.wizard = Some(running);
+        Action::Reload
+    }
+
+    /// Restore the state of the list from before a wizard was started,
+    /// and call its callback.
+    fn finish_wizard(
+        &mut self,
+        running: RunningWizard<'rofi, M::Data>,
+        completed: bool,
+        input: &mut String,
+    ) -> Action {
+        match running.display_name {
+            Some(display_name) => drop(self.cx.api.replace_display_name(display_name)),
+            None => drop(self.cx.api.take_display_name()),
+        }
+        self.cx.restore(running.input, running.selected);
+        let action = running.runner.finish(&mut self.cx, completed);
+        self.finish(action, input)
+    }
+}
+
+#[cfg(feature = "state")]
+use crate::history::History;
+use crate::markup::Markup;
+#[cfg(feature = "state")]
+use crate::pins::Pins;
+use crate::selection::Selection;
+use crate::wizard::Progress;
+use crate::wizard::Wizard;
+use crate::Action;
+use crate::Api;
+use crate::Attributes;
+use crate::Event;
+use crate::Matcher;
+use crate::Mode;
+use crate::String;
+use crate::Style;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::mem;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    struct Numbers;
+
+    impl<'rofi> ListMode<'rofi> for Numbers {
+        const NAME: &'static str = "numbers\0";
+        type Data = ();
+
+        fn init(cx: &mut Context<'rofi, ()>) -> Result<Self, ()> {
+            cx.entries = vec!["one".into(), "two".into(), "three".into()];
+            Ok(Self)
+        }
+
+        fn select(
+            &mut self,
+            _cx: &mut Context<'rofi, ()>,
+            _selected: usize,
+            _alt: bool,
+            _input: &mut String,
+        ) -> Action {
+            Action::Exit
+        }
+    }
+
+    /// Run a function with a list mode that is initialized outside of Rofi.
+    pub(crate) fn with_list<M, F>(f: F)
+    where
+        M: for<'rofi> ListMode<'rofi>,
+        F: FnOnce(&mut List<'_, M>),
+    {
+        let mut raw = ffi::Mode::default();
+        raw.name = M::NAME.as_ptr() as *mut c_char;
+        let api = unsafe { Api::new(ptr::NonNull::from(&mut raw)) };
+        let mut list = List::<M>::init(api).unwrap();
+        f(&mut list);
+        drop(list.cx.api.take_display_name());
+    }
+
+    fn texts(list: &List<'_, Numbers>) -> Vec<std::string::String> {
+        let cx = &list.cx;
+        cx.entries
+            .iter()
+            .map(|entry| entry.text.as_str().to_owned())
+            .collect()
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn entry_text() {
+        let entry = Entry::new("<b>Fish &amp; Chips</b>", ()).with_markup(true);
+        assert_eq!(entry.plain_text().as_str(), "Fish & Chips");
+        assert_eq!(entry.identity().as_str(), "Fish & Chips");
+        assert_eq!(entry.completed().as_str(), "Fish & Chips");
+
+        let mut entry = entry.with_id("fish").with_completion("chips");
+        assert_eq!(entry.identity().as_str(), "fish");
+        assert_eq!(entry.completed().as_str(), "chips");
+
+        entry.text = "<i>Peas</i>".into();
+        assert_eq!(entry.plain_text().as_str(), "Peas");
+        entry.text = "a < b".into();
+        assert_eq!(entry.plain_text().as_str(), "a < b");
+        entry.markup = false;
+        entry.text = "<i>Peas</i>".into();
+        assert_eq!(entry.plain_text().as_str(), "<i>Peas</i>");
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn select() {
+        with_list::<Numbers, _>(|list| {
+            assert_eq!(list.entries(), 3);
+            assert_eq!(list.entry_content(1).as_str(), "two");
+            let mut input = String::new();
+            let ok = Event::Ok {
+                alt: false,
+                selected: 1,
+            };
+            assert_eq!(list.react(ok, &mut input), Action::Exit);
+        });
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn confirm() {
+        with_list::<Numbers, _>(|list| {
+            let mut input = String::from("t");
+            let ask = |cx: &mut Context<'_, ()>| {
+                cx.confirm("Delete <one>?", |cx, yes| {
+                    if yes {
+                        cx.entries.remove(0);
+                    }
+                    Action::Reload
+                });
+            };
+
+            ask(&mut list.cx);
+            assert!(list.cx.is_confirming());
+            assert_eq!(list.message().as_str(), "Delete &lt;one&gt;?");
+            assert_eq!(list.entries(), 2);
+            assert_eq!(list.entry_content(0).as_str(), "Yes");
+            let no = Event::Ok {
+                alt: false,
+                selected: 1,
+            };
+            assert_eq!(list.react(no, &mut input), Action::Reload);
+            assert!(!list.cx.is_confirming());
+            assert_eq!(texts(list), ["one", "two", "three"]);
+
+            ask(&mut list.cx);
+            let ignored = Event::CustomCommand {
+                number: 0,
+                selected: None,
+            };
+            assert_eq!(list.react(ignored, &mut input), Action::Reload);
+            assert!(list.cx.is_confirming());
+            let yes = Event::Ok {
+                alt: false,
+                selected: 0,
+            };
+            assert_eq!(list.react(yes, &mut input), Action::Reload);
+            assert_eq!(texts(list), ["two", "three"]);
+        });
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn wizard() {
+        with_list::<Numbers, _>(|list| {
+            let parse = |input: &str| {
+                input
+                    .parse::<u32>()
+                    .map_err(|_| String::from("not a number"))
+            };
+            let wizard = Wizard::new()
+                .step(Step::new("First", move |sum: &mut [u32; 2], input| {
+                    sum[0] = parse(input)?;
+                    Ok(())
+                }))
+                .step(Step::new("Second", move |sum: &mut [u32; 2], input| {
+                    sum[1] = parse(input)?;
+                    Ok(())
+                }));
+            list.cx.run_wizard(wizard, |cx, sum| {
+                let [a, b] = sum.unwrap();
+                cx.entries.push(crate::format!("{}", a + b).into());
+                Action::Reload
+            });
+            assert!(list.cx.is_running_wizard());
+            assert_eq!(list.cx.api.display_name(), Some("First"));
+
+            let answer = |list: &mut List<'_, Numbers>, text: &str| {
+                let mut input = String::from(text);
+                let event = Event::CustomInput {
+                    alt: false,
+                    selected: None,
+                };
+                list.react(event, &mut input);
+                input
+            };
+            assert_eq!(answer(list, "x").as_str(), "x");
+            assert_eq!(list.message().as_str(), "not a number");
+            assert_eq!(answer(list, "2").as_str(), "");
+            assert_eq!(list.cx.api.display_name(), Some("Second"));
+
+            let mut input = String::new();
+            list.react(Event::Cancel { selected: None }, &mut input);
+            assert_eq!(input.as_str(), "2");
+            assert_eq!(list.cx.api.display_name(), Some("First"));
+
+            answer(list, "2");
+            answer(list, "3");
+            assert!(!list.cx.is_running_wizard());
+            assert_eq!(list.cx.api.display_name(), None);
+            assert_eq!(texts(list), ["one", "two", "three", "5"]);
+        });
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn stack() {
+        with_list::<Numbers, _>(|list| {
+            list.cx.push(Menu::new("Child", vec!["a".into()]));
+            assert_eq!(list.cx.depth(), 1);
+            assert_eq!(list.cx.api.display_name(), Some("numbers / Child"));
+            assert_eq!(texts(list), ["a"]);
+
+            let mut input = String::new();
+            let cancel = Event::Cancel { selected: None };
+            assert_eq!(list.react(cancel, &mut input), Action::Reload);
+            assert_eq!(list.cx.depth(), 0);
+            assert_eq!(texts(list), ["one", "two", "three"]);
+            assert_eq!(list.react(cancel, &mut input), Action::Exit);
+        });
+    }
+
+    use super::Context;
+    use super::Entry;
+    use super::List;
+    use super::ListMode;
+    use super::Menu;
+    use crate::ffi;
+    use crate::wizard::Step;
+    use crate::wizard::Wizard;
+    use crate::Action;
+    use crate::Api;
+    use crate::Event;
+    use crate::Mode;
+    use crate::String;
+    use std::os::raw::c_char;
+    use std::ptr;
+}
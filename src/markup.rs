@@ -0,0 +1,486 @@
+//! Building [Pango markup] without forgetting to escape text.
+//!
+//! Entries with [`Style::MARKUP`] and the [message bar](crate::Mode::message)
+//! are rendered as Pango markup,
+//! so any `&`, `<` or `>` in text shown there must be escaped.
+//! [`Markup`] is a builder that escapes all text given to it,
+//! and [`markup!`](crate::markup!) is a version of [`format!`](crate::format!)
+//! that escapes its arguments.
+//!
+//! ```
+//! use rofi_mode::markup;
+//! use rofi_mode::markup::Markup;
+//! use rofi_mode::markup::Span;
+//!
+//! let name = "Fish & Chips";
+//! let markup = Markup::new()
+//!     .bold(name)
+//!     .text(" ")
+//!     .span(&Span::new().foreground("grey").size("small"), "<takeaway>");
+//! assert_eq!(
+//!     markup.as_str(),
+//!     "<b>Fish &amp; Chips</b> <span foreground=\"grey\" size=\"small\">&lt;takeaway&gt;</span>",
+//! );
+//!
+//! let markup = markup!("<i>{}</i>: {}", name, Markup::new().bold("£4"));
+//! assert_eq!(markup.as_str(), "<i>Fish &amp; Chips</i>: <b>£4</b>");
+//! ```
+//!
+//! [Pango markup]: https://docs.gtk.org/Pango/pango_markup.html
+//! [`Style::MARKUP`]: crate::Style::MARKUP
+
+/// A string of Pango markup.
+///
+/// Text added to the markup is escaped,
+/// while other [`Markup`] is added as-is.
+/// Every method that takes content accepts both:
+/// a string is converted into markup by escaping it.
+///
+/// See [the module documentation](self) for more.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Markup {
+    string: String,
+}
+
+impl Markup {
+    /// Create empty markup.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create markup from a string that is already valid Pango markup,
+    /// without escaping it.
+    #[must_use]
+    pub fn raw<S: Into<String>>(markup: S) -> Self {
+        Self {
+            string: markup.into(),
+        }
+    }
+
+    /// Append text, escaping it.
+    #[must_use]
+    pub fn text(mut self, text: &str) -> Self {
+        push_escaped(&mut self.string, text);
+        self
+    }
+
+    /// Append content without any formatting.
+    #[must_use]
+    pub fn push<C: Into<Self>>(mut self, content: C) -> Self {
+        self.string.push_str(&content.into().string);
+        self
+    }
+
+    /// Append content in bold.
+    #[must_use]
+    pub fn bold<C: Into<Self>>(self, content: C) -> Self {
+        self.tag("b", content)
+    }
+
+    /// Append content in italics.
+    #[must_use]
+    pub fn italic<C: Into<Self>>(self, content: C) -> Self {
+        self.tag("i", content)
+    }
+
+    /// Append underlined content.
+    #[must_use]
+    pub fn underline<C: Into<Self>>(self, content: C) -> Self {
+        self.tag("u", content)
+    }
+
+    /// Append struck-through content.
+    #[must_use]
+    pub fn strikethrough<C: Into<Self>>(self, content: C) -> Self {
+        self.tag("s", content)
+    }
+
+    /// Append content in a monospace font.
+    #[must_use]
+    pub fn monospace<C: Into<Self>>(self, content: C) -> Self {
+        self.tag("tt", content)
+    }
+
+    /// Append content in the given foreground colour,
+    /// either a name like `red` or a hex code like `#ff0000`.
+    #[must_use]
+    pub fn color<C: Into<Self>>(self, color: &str, content: C) -> Self {
+        self.span(&Span::new().foreground(color), content)
+    }
+
+    /// Append content in the given font size,
+    /// for example `small`, `x-large` or `12pt`.
+    #[must_use]
+    pub fn size<C: Into<Self>>(self, size: &str, content: C) -> Self {
+        self.span(&Span::new().size(size), content)
+    }
+
+    /// Append content in a `<span>` with the given attributes.
+    #[must_use]
+    pub fn span<C: Into<Self>>(mut self, span: &Span, content: C) -> Self {
+        self.string.push_str("<span");
+        self.string.push_str(&span.attributes);
+        self.string.push('>');
+        self.string.push_str(&content.into().string);
+        self.string.push_str("</span>");
+        self
+    }
+
+    /// Whether the markup is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.string.is_empty()
+    }
+
+    /// Get the markup as a string slice.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.string.as_str()
+    }
+
+    /// Convert the markup into a Rofi [`String`].
+    #[must_use]
+    pub fn into_string(self) -> String {
+        self.string
+    }
+
+    fn tag<C: Into<Self>>(mut self, tag: &str, content: C) -> Self {
+        self.string.push('<');
+        self.string.push_str(tag);
+        self.string.push('>');
+        self.string.push_str(&content.into().string);
+        self.string.push_str("</");
+        self.string.push_str(tag);
+        self.string.push('>');
+        self
+    }
+}
+
+impl From<&str> for Markup {
+    fn from(text: &str) -> Self {
+        Self::new().text(text)
+    }
+}
+
+impl From<&String> for Markup {
+    fn from(text: &String) -> Self {
+        Self::new().text(text)
+    }
+}
+
+impl From<String> for Markup {
+    fn from(text: String) -> Self {
+        Self::new().text(&text)
+    }
+}
+
+impl From<&std::string::String> for Markup {
+    fn from(text: &std::string::String) -> Self {
+        Self::new().text(text)
+    }
+}
+
+impl From<std::string::String> for Markup {
+    fn from(text: std::string::String) -> Self {
+        Self::new().text(&text)
+    }
+}
+
+impl From<Markup> for String {
+    fn from(markup: Markup) -> Self {
+        markup.string
+    }
+}
+
+impl Display for Markup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The attributes of a `<span>` in [`Markup`].
+///
+/// Attribute values are escaped.
+/// See [Pango's documentation][attributes] for the supported attributes.
+///
+/// [attributes]: https://docs.gtk.org/Pango/pango_markup.html#the-span-attributes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    attributes: String,
+}
+
+impl Span {
+    /// Create a span with no attributes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an attribute to the span.
+    #[must_use]
+    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push(' ');
+        self.attributes.push_str(name);
+        self.attributes.push_str("=\"");
+        push_escaped(&mut self.attributes, value);
+        self.attributes.push('"');
+        self
+    }
+
+    /// Set the foreground colour,
+    /// either a name like `red` or a hex code like `#ff0000`.
+    #[must_use]
+    pub fn foreground(self, color: &str) -> Self {
+        self.attribute("foreground", color)
+    }
+
+    /// Set the background colour,
+    /// either a name like `red` or a hex code like `#ff0000`.
+    #[must_use]
+    pub fn background(self, color: &str) -> Self {
+        self.attribute("background", color)
+    }
+
+    /// Set the font size,
+    /// for example `small`, `x-large` or `12pt`.
+    #[must_use]
+    pub fn size(self, size: &str) -> Self {
+        self.attribute("size", size)
+    }
+
+    /// Set the font weight,
+    /// for example `bold`, `light` or `600`.
+    #[must_use]
+    pub fn weight(self, weight: &str) -> Self {
+        self.attribute("weight", weight)
+    }
+
+    /// Set the font family, for example `monospace`.
+    #[must_use]
+    pub fn font_family(self, family: &str) -> Self {
+        self.attribute("font_family", family)
+    }
+}
+
+/// Create [`Markup`] using interpolation of runtime expressions,
+/// escaping the arguments.
+///
+/// This takes the same arguments as [`format!`](crate::format!).
+/// Arguments that are [`Markup`] are inserted as-is,
+/// while any other argument is formatted and then escaped.
+/// The format string itself is not escaped,
+/// so it can contain markup.
+///
+/// Variables can't be captured directly in the format string, as in `{name}`,
+/// since they couldn't be escaped;
+/// pass them as arguments instead:
+///
+/// ```compile_fail
+/// let name = "Fish & Chips";
+/// let markup = rofi_mode::markup!("<i>{name}</i>");
+/// ```
+///
+/// See [the `markup` module](mod@crate::markup) for an example.
+#[macro_export]
+macro_rules! markup {
+    (@args $fmt:literal [$($done:tt)*] [$($names:expr,)*]) => {{
+        #[allow(unused_imports)]
+        use $crate::markup::__private::{Escaped as _, Verbatim as _};
+        const _: () = $crate::markup::__private::check_captures($fmt, &[$($names),*]);
+        $crate::markup::Markup::raw($crate::format!($fmt, $($done)*))
+    }};
+    (@args $fmt:literal [$($done:tt)*] [$($names:tt)*] $name:ident = $arg:expr $(, $($rest:tt)*)?) => {
+        $crate::markup!(@args $fmt [
+            $($done)* $name = (&$crate::markup::__private::Arg(&$arg)).markup_arg(),
+        ] [$($names)* ::core::stringify!($name),] $($($rest)*)?)
+    };
+    (@args $fmt:literal [$($done:tt)*] [$($names:tt)*] $arg:expr $(, $($rest:tt)*)?) => {
+        $crate::markup!(@args $fmt [
+            $($done)* (&$crate::markup::__private::Arg(&$arg)).markup_arg(),
+        ] [$($names)*] $($($rest)*)?)
+    };
+    ($fmt:literal $(, $($args:tt)*)?) => {
+        $crate::markup!(@args $fmt [] [] $($($args)*)?)
+    };
+}
+
+#[doc(hidden)]
+#[allow(missing_debug_implementations)]
+pub mod __private {
+    pub struct Arg<'a, T: ?Sized>(pub &'a T);
+
+    /// Chosen by method resolution over [`Escaped`] when the argument is [`Markup`].
+    pub trait Verbatim {
+        fn markup_arg(&self) -> &str;
+    }
+
+    impl Verbatim for Arg<'_, Markup> {
+        fn markup_arg(&self) -> &str {
+            self.0.as_str()
+        }
+    }
+
+    impl Verbatim for Arg<'_, &Markup> {
+        fn markup_arg(&self) -> &str {
+            self.0.as_str()
+        }
+    }
+
+    pub trait Escaped<'a, T: ?Sized> {
+        fn markup_arg(&self) -> Escape<'a, T>;
+    }
+
+    impl<'a, T: ?Sized> Escaped<'a, T> for &Arg<'a, T> {
+        fn markup_arg(&self) -> Escape<'a, T> {
+            Escape(self.0)
+        }
+    }
+
+    pub struct Escape<'a, T: ?Sized>(&'a T);
+
+    impl<T: ?Sized + Display> Display for Escape<'_, T> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(EscapeWriter(f), "{}", self.0)
+        }
+    }
+
+    impl<T: ?Sized + Debug> Debug for Escape<'_, T> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(EscapeWriter(f), "{:?}", self.0)
+        }
+    }
+
+    /// Fail to compile if the format string refers to a name that isn't an argument,
+    /// which would capture a variable without escaping it.
+    pub const fn check_captures(fmt: &str, names: &[&str]) {
+        let fmt = fmt.as_bytes();
+        let mut i = 0;
+        while i < fmt.len() {
+            if fmt[i] != b'{' {
+                i += 1;
+                continue;
+            }
+            if i + 1 < fmt.len() && fmt[i + 1] == b'{' {
+                i += 2;
+                continue;
+            }
+            let start = i + 1;
+            let mut end = start;
+            while end < fmt.len() && fmt[end] != b'}' && fmt[end] != b':' {
+                end += 1;
+            }
+            let is_name = end > start && !fmt[start].is_ascii_digit();
+            assert!(
+                !is_name || contains(names, fmt, start, end),
+                "`markup!` can't escape captured variables; pass them as arguments",
+            );
+            i = end;
+        }
+    }
+
+    /// Whether `names` contains `fmt[start..end]`.
+    const fn contains(names: &[&str], fmt: &[u8], start: usize, end: usize) -> bool {
+        let mut n = 0;
+        while n < names.len() {
+            let name = names[n].as_bytes();
+            if name.len() == end - start {
+                let mut i = 0;
+                while i < name.len() && name[i] == fmt[start + i] {
+                    i += 1;
+                }
+                if i == name.len() {
+                    return true;
+                }
+            }
+            n += 1;
+        }
+        false
+    }
+
+    struct EscapeWriter<'a, 'b>(&'a mut Formatter<'b>);
+
+    impl fmt::Write for EscapeWriter<'_, '_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            for c in s.chars() {
+                match escape(c) {
+                    Some(escaped) => self.0.write_str(escaped)?,
+                    None => self.0.write_char(c)?,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    use super::escape;
+    use super::Markup;
+    use std::fmt;
+    use std::fmt::Debug;
+    use std::fmt::Display;
+    use std::fmt::Formatter;
+    use std::fmt::Write as _;
+}
+
+/// Append text to a string, escaping it for use in markup.
+fn push_escaped(string: &mut String, text: &str) {
+    for c in text.chars() {
+        match escape(c) {
+            Some(escaped) => string.push_str(escaped),
+            None => string.push(c),
+        }
+    }
+}
+
+fn escape(c: char) -> Option<&'static str> {
+    Some(match c {
+        '&' => "&amp;",
+        '<' => "&lt;",
+        '>' => "&gt;",
+        '"' => "&quot;",
+        '\'' => "&#39;",
+        _ => return None,
+    })
+}
+
+use crate::String;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn escaping() {
+        let markup = Markup::new()
+            .text("a & b")
+            .bold(Markup::new().italic("<i>"))
+            .span(&Span::new().attribute("font", "\"x\""), "'");
+        assert_eq!(
+            markup.as_str(),
+            "a &amp; b<b><i>&lt;i&gt;</i></b><span font=\"&quot;x&quot;\">&#39;</span>",
+        );
+
+        let value = 3;
+        let markup = crate::markup!(
+            "<b>{}</b> {:?} {x} {y}",
+            "1 < 2",
+            "&",
+            x = value,
+            y = Markup::new().monospace("y"),
+        );
+        assert_eq!(
+            markup.as_str(),
+            "<b>1 &lt; 2</b> &quot;&amp;&quot; 3 <tt>y</tt>",
+        );
+    }
+
+    #[test]
+    fn markup_references() {
+        let bold = Markup::new().bold("a & b");
+        let markup = crate::markup!("{} {b} {{c}}", &bold, b = &bold);
+        assert_eq!(markup.as_str(), "<b>a &amp; b</b> <b>a &amp; b</b> {c}",);
+    }
+
+    use super::Markup;
+    use super::Span;
+}
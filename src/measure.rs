@@ -0,0 +1,105 @@
+//! Measuring text as Rofi will render it.
+
+/// A measurer of the width of text in a font.
+pub(crate) struct Measure {
+    layout: pango::Layout,
+    font: pango::FontDescription,
+}
+
+impl Measure {
+    /// Create a measurer for the given Pango font description,
+    /// or the font of the current theme's entries if there is none.
+    pub(crate) fn new(font: Option<&str>) -> Self {
+        let theme_font = if font.is_none() { theme_font() } else { None };
+        let font = font.or(theme_font.as_deref()).unwrap_or(DEFAULT_FONT);
+        let font = pango::FontDescription::from_string(font);
+        let font_map: pango::FontMap =
+            unsafe { from_glib_none(pango_cairo_font_map_get_default()) };
+        let layout = pango::Layout::new(&font_map.create_context());
+        layout.set_font_description(Some(&font));
+        Self { layout, font }
+    }
+
+    /// Get the width of text in pixels.
+    pub(crate) fn width(&self, text: &str) -> i32 {
+        self.layout.set_text(text);
+        self.layout.pixel_size().0
+    }
+
+    /// Get the approximate width of a character in pixels.
+    pub(crate) fn char_width(&self) -> i32 {
+        let metrics = self.layout.context().metrics(Some(&self.font), None);
+        metrics.approximate_char_width() / pango::SCALE
+    }
+}
+
+/// Get the width of the window set by the current theme in pixels.
+pub(crate) fn window_width() -> Option<i32> {
+    let widget = unsafe { rofi_theme_find_widget(c_str(b"window\0"), ptr::null(), 0) };
+    if widget.is_null() {
+        return None;
+    }
+    let property = unsafe {
+        ffi::helper::theme_find_property(widget, ffi::PropertyType::Padding, c_str(b"width\0"), 0)
+    };
+    if property.is_null() {
+        return None;
+    }
+    // Rofi stores a single distance in the left side of a padding.
+    let distance = unsafe { (*property).value.padding.left };
+    let width = unsafe { distance_get_pixel(distance, ffi::RofiOrientation::Horizontal) };
+    Some(width).filter(|&width| width > 0)
+}
+
+/// The font Rofi uses when the theme doesn't set one.
+const DEFAULT_FONT: &str = "mono 12";
+
+/// Get the font of the current theme's entries.
+fn theme_font() -> Option<String> {
+    let widget = unsafe { rofi_theme_find_widget(c_str(b"element-text\0"), ptr::null(), 0) };
+    if widget.is_null() {
+        return None;
+    }
+    let property = unsafe {
+        ffi::helper::theme_find_property(widget, ffi::PropertyType::String, c_str(b"font\0"), 0)
+    };
+    if property.is_null() {
+        return None;
+    }
+    let value = unsafe { (*property).value.s };
+    if value.is_null() {
+        return None;
+    }
+    let font = unsafe { CStr::from_ptr(value) }.to_string_lossy();
+    Some(font.into_owned())
+}
+
+fn c_str(bytes: &'static [u8]) -> *const c_char {
+    CStr::from_bytes_with_nul(bytes).unwrap().as_ptr()
+}
+
+extern "C" {
+    fn rofi_theme_find_widget(
+        name: *const c_char,
+        state: *const c_char,
+        exact: glib_sys::gboolean,
+    ) -> *mut ffi::ThemeWidget;
+    fn distance_get_pixel(distance: ffi::RofiDistance, orientation: ffi::RofiOrientation) -> c_int;
+}
+
+// There are no bindings to PangoCairo compatible with the version of `pango` used,
+// so link to it directly.
+#[link(name = "pangocairo-1.0")]
+extern "C" {
+    fn pango_cairo_font_map_get_default() -> *mut pango_sys::PangoFontMap;
+}
+
+use crate::ffi;
+use pango::ffi as pango_sys;
+use pango::glib::ffi as glib_sys;
+use pango::glib::translate::from_glib_none;
+use pango::prelude::FontMapExt;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::os::raw::c_int;
+use std::ptr;
@@ -0,0 +1,324 @@
+//! Transient messages shown in the message bar.
+//!
+//! A [`Notification`] is queued with [`Api::notify`] or [`Api::notify_with`]
+//! and shown above the mode's own [message](crate::Mode::message)
+//! until it expires.
+//! By default a notification lasts until the user triggers the next event,
+//! but it can instead last for several events or for a fixed amount of time.
+//!
+//! Notifications are styled according to their [`Severity`].
+//!
+//! [`Api::notify`]: crate::Api::notify
+//! [`Api::notify_with`]: crate::Api::notify_with
+
+/// A transient message shown in the message bar.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    message: String,
+    severity: Severity,
+    events: u32,
+    timeout: Option<Duration>,
+}
+
+impl Notification {
+    /// Create a notification lasting until the next event.
+    ///
+    /// The message is plain text, not markup.
+    #[must_use]
+    pub fn new<M: Into<String>>(message: M, severity: Severity) -> Self {
+        Self {
+            message: message.into(),
+            severity,
+            events: 1,
+            timeout: None,
+        }
+    }
+
+    /// Set the number of events the notification lasts for.
+    ///
+    /// The notification is removed after the user has triggered this many events.
+    /// Zero means it is only removed by its [timeout](Self::with_timeout).
+    #[must_use]
+    pub fn with_events(mut self, events: u32) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Remove the notification once the given amount of time has passed,
+    /// reloading the view to hide it.
+    ///
+    /// The notification is also still removed after its [events](Self::with_events) run out,
+    /// whichever comes first.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Get the message of the notification.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the severity of the notification.
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Render the notification as Pango markup.
+    fn markup(&self) -> Markup {
+        let message = self.message.as_str();
+        match self.severity {
+            Severity::Info => Markup::from(message),
+            Severity::Success => Markup::new().color("#4e9a06", message),
+            Severity::Warning => {
+                Markup::new().span(&Span::new().foreground("#c4a000").weight("bold"), message)
+            }
+            Severity::Error => {
+                Markup::new().span(&Span::new().foreground("#cc0000").weight("bold"), message)
+            }
+        }
+    }
+}
+
+/// How important a [`Notification`] is,
+/// which determines how it is styled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// General information, shown as plain text.
+    Info,
+    /// The result of a successful action, shown in green.
+    Success,
+    /// Something that may need the user's attention, shown in bold yellow.
+    Warning,
+    /// A failure, shown in bold red.
+    Error,
+}
+
+/// The notifications of every mode.
+struct Queue {
+    /// The queued notifications, oldest first.
+    queued: Vec<Queued>,
+    /// The ID given to the next notification.
+    next_id: u64,
+    /// Whether an event is being handled.
+    handling_event: bool,
+}
+
+struct Queued {
+    id: u64,
+    mode: *const ffi::Mode,
+    notification: Notification,
+    /// Whether the notification was queued while handling the current event,
+    /// in which case that event does not count towards its expiry.
+    fresh: bool,
+    /// The number of events left before the notification expires,
+    /// if it expires after a number of events.
+    events_left: Option<u32>,
+}
+
+impl Queue {
+    const fn new() -> Self {
+        Self {
+            queued: Vec::new(),
+            next_id: 0,
+            handling_event: false,
+        }
+    }
+
+    /// Queue a notification for the given mode, returning its ID.
+    fn push(&mut self, mode: *const ffi::Mode, notification: Notification) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.queued.push(Queued {
+            id,
+            mode,
+            events_left: Some(notification.events).filter(|&events| events > 0),
+            notification,
+            fresh: self.handling_event,
+        });
+        id
+    }
+
+    /// Remove the notification with the given ID, if it is still queued.
+    fn remove(&mut self, id: u64) {
+        self.queued.retain(|queued| queued.id != id);
+    }
+
+    /// Remove every notification of the given mode.
+    fn clear(&mut self, mode: *const ffi::Mode) {
+        self.queued.retain(|queued| !ptr::eq(queued.mode, mode));
+    }
+
+    /// Count an event handled by the given mode towards the expiry of its notifications.
+    fn event_handled(&mut self, mode: *const ffi::Mode) {
+        self.handling_event = false;
+        for queued in self
+            .queued
+            .iter_mut()
+            .filter(|queued| ptr::eq(queued.mode, mode))
+        {
+            if !mem::replace(&mut queued.fresh, false) {
+                queued.events_left = queued.events_left.map(|events| events - 1);
+            }
+        }
+        self.queued.retain(|queued| queued.events_left != Some(0));
+    }
+
+    /// Add the notifications of the given mode to the start of its message.
+    fn decorate(&self, mode: *const ffi::Mode, message: String) -> String {
+        let mut decorated = String::new();
+        for queued in self
+            .queued
+            .iter()
+            .filter(|queued| ptr::eq(queued.mode, mode))
+        {
+            if !decorated.is_empty() {
+                decorated.push('\n');
+            }
+            decorated.push_str(queued.notification.markup().as_str());
+        }
+        if decorated.is_empty() {
+            return message;
+        }
+        if !message.is_empty() {
+            decorated.push('\n');
+            decorated.push_str(&message);
+        }
+        decorated
+    }
+}
+
+thread_local! {
+    static QUEUE: RefCell<Queue> = const { RefCell::new(Queue::new()) };
+}
+
+/// Queue a notification for the given mode.
+pub(crate) fn push(mode: *const ffi::Mode, notification: Notification) {
+    let timeout = notification.timeout;
+    let id = QUEUE.with(|queue| queue.borrow_mut().push(mode, notification));
+    if let Some(timeout) = timeout {
+        let millis = c_uint::try_from(timeout.as_millis()).unwrap_or(c_uint::MAX);
+        let data = id as usize as glib_sys::gpointer;
+        unsafe { glib_sys::g_timeout_add(millis, Some(expire), data) };
+    }
+}
+
+unsafe extern "C" fn expire(id: glib_sys::gpointer) -> glib_sys::gboolean {
+    QUEUE.with(|queue| queue.borrow_mut().remove(id as usize as u64));
+    // Reloading the view fetches the message again along with the entries.
+    unsafe { ffi::view::reload() };
+    glib_sys::G_SOURCE_REMOVE
+}
+
+/// Remove every notification of the given mode.
+pub(crate) fn clear(mode: *const ffi::Mode) {
+    QUEUE.with(|queue| queue.borrow_mut().clear(mode));
+}
+
+/// Handle an event of the given mode with `f`,
+/// counting it towards the expiry of the notifications queued before it.
+pub(crate) fn handle_event<R, F: FnOnce() -> R>(mode: *const ffi::Mode, f: F) -> R {
+    QUEUE.with(|queue| queue.borrow_mut().handling_event = true);
+    let res = f();
+    QUEUE.with(|queue| queue.borrow_mut().event_handled(mode));
+    res
+}
+
+/// Add the notifications of the given mode to the start of its message.
+pub(crate) fn decorate(mode: *const ffi::Mode, message: String) -> String {
+    QUEUE.with(|queue| queue.borrow().decorate(mode, message))
+}
+
+use crate::ffi;
+use crate::markup::Markup;
+use crate::markup::Span;
+use crate::String;
+use pango::glib::ffi as glib_sys;
+use std::cell::RefCell;
+use std::mem;
+use std::os::raw::c_uint;
+use std::ptr;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    fn messages(queue: &Queue, mode: &ffi::Mode) -> std::string::String {
+        queue.decorate(mode, String::new()).as_str().to_owned()
+    }
+
+    #[test]
+    fn events() {
+        let modes = [ffi::Mode::default(), ffi::Mode::default()];
+        let mut queue = Queue::new();
+
+        queue.push(&modes[0], Notification::new("a", Severity::Info));
+        queue.push(
+            &modes[0],
+            Notification::new("b", Severity::Info).with_events(2),
+        );
+        queue.push(&modes[1], Notification::new("c", Severity::Info));
+        assert_eq!(messages(&queue, &modes[0]), "a\nb");
+
+        queue.event_handled(&modes[0]);
+        assert_eq!(messages(&queue, &modes[0]), "b");
+        assert_eq!(messages(&queue, &modes[1]), "c");
+
+        // A notification queued while handling an event outlives that event.
+        queue.handling_event = true;
+        queue.push(&modes[0], Notification::new("d", Severity::Info));
+        queue.event_handled(&modes[0]);
+        assert_eq!(messages(&queue, &modes[0]), "d");
+        queue.event_handled(&modes[0]);
+        assert_eq!(messages(&queue, &modes[0]), "");
+        assert_eq!(messages(&queue, &modes[1]), "c");
+    }
+
+    #[test]
+    fn removal() {
+        let modes = [ffi::Mode::default(), ffi::Mode::default()];
+        let mut queue = Queue::new();
+
+        let id = queue.push(
+            &modes[0],
+            Notification::new("a", Severity::Info).with_events(0),
+        );
+        queue.push(
+            &modes[0],
+            Notification::new("b", Severity::Info).with_events(0),
+        );
+        queue.push(&modes[1], Notification::new("c", Severity::Info));
+        for _ in 0..3 {
+            queue.event_handled(&modes[0]);
+        }
+        assert_eq!(messages(&queue, &modes[0]), "a\nb");
+
+        queue.remove(id);
+        assert_eq!(messages(&queue, &modes[0]), "b");
+        queue.clear(&modes[0]);
+        assert_eq!(messages(&queue, &modes[0]), "");
+        assert_eq!(messages(&queue, &modes[1]), "c");
+    }
+
+    #[test]
+    fn decorate() {
+        let mode = ffi::Mode::default();
+        let mut queue = Queue::new();
+        assert_eq!(queue.decorate(&mode, "message".into()).as_str(), "message");
+
+        queue.push(&mode, Notification::new("a < b", Severity::Info));
+        queue.push(&mode, Notification::new("done", Severity::Success));
+        assert_eq!(
+            queue.decorate(&mode, "message".into()).as_str(),
+            "a &lt; b\n<span foreground=\"#4e9a06\">done</span>\nmessage",
+        );
+    }
+
+    use super::Notification;
+    use super::Queue;
+    use super::Severity;
+    use crate::ffi;
+    use crate::String;
+}
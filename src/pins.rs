@@ -0,0 +1,270 @@
+//! Letting the user pin entries to the top of a list.
+//!
+//! [`Pins`] is an ordered list of pinned entries,
+//! stored in the mode's [state directory](crate::state) as `pins.json`.
+//! Pinned entries always appear first, in the order they were pinned,
+//! and are displayed with [`Style::ACTIVE`].
+//!
+//! A [`ListMode`] can opt in to pins by setting [`Context::pins`] in its `init` function.
+//! The user can then pin and unpin the selected entry with a custom command
+//! (for example `kb-custom-1`, i.e. alt+1, for custom command 0)
+//! and optionally move pinned entries up and down with two more.
+//! Those commands are handled by the list
+//! and never reach [`ListMode::react`].
+//! Entries are identified by their [identity](crate::list::Entry::identity).
+//!
+//! ```no_run
+//! use rofi_mode::list;
+//! use rofi_mode::pins::Pins;
+//!
+//! struct Mode;
+//!
+//! impl rofi_mode::ListMode<'_> for Mode {
+//!     const NAME: &'static str = "an-example-list-mode\0";
+//!     type Data = ();
+//!
+//!     fn init(cx: &mut list::Context<'_, ()>) -> Result<Self, ()> {
+//!         cx.pins = Some(Pins::load(&cx.api).with_toggle_command(0).with_move_commands(1, 2));
+//!         cx.entries.extend(["example.com", "example.org"].map(list::Entry::from));
+//!         Ok(Self)
+//!     }
+//!     # fn select(
+//!     #     &mut self,
+//!     #     _cx: &mut list::Context<'_, ()>,
+//!     #     _selected: usize,
+//!     #     _alt: bool,
+//!     #     _input: &mut rofi_mode::String,
+//!     # ) -> rofi_mode::Action {
+//!     #     rofi_mode::Action::Exit
+//!     # }
+//! }
+//! ```
+//!
+//! This module requires the `state` feature.
+//!
+//! [`ListMode`]: crate::ListMode
+//! [`ListMode::react`]: crate::ListMode::react
+//! [`Context::pins`]: crate::list::Context::pins
+//! [`Style::ACTIVE`]: crate::Style::ACTIVE
+
+/// A persistent, ordered list of pinned entries.
+///
+/// See [the module documentation](self) for more.
+#[derive(Debug)]
+pub struct Pins {
+    state: State<PinList>,
+    toggle_command: Option<u8>,
+    move_commands: Option<(u8, u8)>,
+}
+
+impl Pins {
+    /// Load the pins of the current mode,
+    /// starting with no pins if they can't be loaded.
+    ///
+    /// No custom commands are handled by default.
+    #[must_use]
+    pub fn load(api: &Api<'_>) -> Self {
+        Self {
+            state: State::load_named(api, "pins"),
+            toggle_command: None,
+            move_commands: None,
+        }
+    }
+
+    /// Set the number of the custom command that pins or unpins the selected entry.
+    #[must_use]
+    pub fn with_toggle_command(mut self, number: u8) -> Self {
+        self.toggle_command = Some(number);
+        self
+    }
+
+    /// Set the numbers of the custom commands
+    /// that move the selected pinned entry up and down respectively.
+    ///
+    /// A command that is also the toggle command only toggles.
+    #[must_use]
+    pub fn with_move_commands(mut self, up: u8, down: u8) -> Self {
+        self.move_commands = Some((up, down));
+        self
+    }
+
+    /// Get the identities of the pinned entries, in order.
+    #[must_use]
+    pub fn pinned(&self) -> &[String] {
+        &self.state.0
+    }
+
+    /// Get the position of the entry with the given identity among the pinned entries,
+    /// or [`None`] if it isn't pinned.
+    #[must_use]
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.state.0.iter().position(|pinned| pinned == id)
+    }
+
+    /// Whether the entry with the given identity is pinned.
+    #[must_use]
+    pub fn is_pinned(&self, id: &str) -> bool {
+        self.position(id).is_some()
+    }
+
+    /// Pin the entry with the given identity after all the other pinned entries,
+    /// if it isn't pinned already.
+    pub fn pin(&mut self, id: &str) {
+        if !self.is_pinned(id) {
+            self.state.0.push(id.to_owned());
+        }
+    }
+
+    /// Unpin the entry with the given identity, if it is pinned.
+    pub fn unpin(&mut self, id: &str) {
+        if let Some(i) = self.position(id) {
+            self.state.0.remove(i);
+        }
+    }
+
+    /// Pin the entry with the given identity if it isn't pinned,
+    /// and unpin it otherwise.
+    pub fn toggle(&mut self, id: &str) {
+        match self.position(id) {
+            Some(i) => drop(self.state.0.remove(i)),
+            None => self.state.0.push(id.to_owned()),
+        }
+    }
+
+    /// Move a pinned entry by the given number of positions,
+    /// where negative numbers move it towards the top.
+    ///
+    /// The entry stops at the top or bottom of the pinned entries,
+    /// and nothing happens if it isn't pinned.
+    pub fn move_by(&mut self, id: &str, offset: isize) {
+        let from = match self.position(id) {
+            Some(from) => from,
+            None => return,
+        };
+        let to = if offset < 0 {
+            from.saturating_sub(offset.unsigned_abs())
+        } else {
+            from.saturating_add(offset.unsigned_abs())
+                .min(self.state.0.len() - 1)
+        };
+        if from != to {
+            let pinned = self.state.0.remove(from);
+            self.state.0.insert(to, pinned);
+        }
+    }
+
+    /// Stably sort a list of entries so that pinned entries come first,
+    /// in the order they are pinned.
+    pub fn sort<T>(&self, entries: &mut [Entry<T>]) {
+        entries.sort_by_cached_key(|entry| {
+            self.position(entry.identity().as_str())
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    /// Handle a custom command, if it is one of the pin commands,
+    /// returning whether it was handled.
+    pub(crate) fn handle_command(&mut self, number: u8, id: &str) -> bool {
+        if self.toggle_command == Some(number) {
+            self.toggle(id);
+        } else if let Some((up, down)) = self.move_commands {
+            if number == up {
+                self.move_by(id, -1);
+            } else if number == down {
+                self.move_by(id, 1);
+            } else {
+                return false;
+            }
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Save the pins to disk.
+    ///
+    /// This happens automatically when the pins are dropped,
+    /// but calling this allows handling errors.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the pins could not be written.
+    pub fn save(&mut self) -> io::Result<()> {
+        self.state.save()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct PinList(Vec<String>);
+
+impl Schema for PinList {}
+
+use crate::list::Entry;
+use crate::state::Schema;
+use crate::state::State;
+use crate::Api;
+use serde::Deserialize;
+use serde::Serialize;
+use std::io;
+
+#[cfg(test)]
+mod tests {
+    fn pins(pinned: &[&str]) -> Pins {
+        let mut state = State::<PinList>::new(PathBuf::new());
+        state.0 = pinned.iter().map(|&id| id.to_owned()).collect();
+        Pins {
+            state,
+            toggle_command: None,
+            move_commands: None,
+        }
+    }
+
+    #[test]
+    fn move_by() {
+        let mut pins = pins(&["a", "b", "c"]);
+        pins.move_by("a", 1);
+        assert_eq!(pins.pinned(), ["b", "a", "c"]);
+        pins.move_by("a", isize::MAX);
+        assert_eq!(pins.pinned(), ["b", "c", "a"]);
+        pins.move_by("a", -1);
+        assert_eq!(pins.pinned(), ["b", "a", "c"]);
+        pins.move_by("a", isize::MIN);
+        assert_eq!(pins.pinned(), ["a", "b", "c"]);
+        pins.move_by("a", -1);
+        pins.move_by("d", 1);
+        assert_eq!(pins.pinned(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn handle_command() {
+        let mut pins = pins(&["a", "b"]).with_toggle_command(0);
+        assert!(!pins.handle_command(1, "a"));
+        assert!(pins.handle_command(0, "c"));
+        assert_eq!(pins.pinned(), ["a", "b", "c"]);
+
+        let mut pins = pins.with_move_commands(0, 1);
+        assert!(pins.handle_command(1, "a"));
+        assert_eq!(pins.pinned(), ["b", "a", "c"]);
+        // The toggle command takes precedence over moving up.
+        assert!(pins.handle_command(0, "a"));
+        assert_eq!(pins.pinned(), ["b", "c"]);
+        assert!(!pins.handle_command(2, "b"));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn sort() {
+        let pins = pins(&["c", "a"]);
+        let mut entries: Vec<Entry<()>> = ["a", "b", "c", "d"].map(Entry::from).into();
+        pins.sort(&mut entries);
+        let texts: Vec<_> = entries.iter().map(|entry| entry.text.as_str()).collect();
+        assert_eq!(texts, ["c", "a", "b", "d"]);
+    }
+
+    use super::PinList;
+    use super::Pins;
+    use crate::list::Entry;
+    use crate::state::State;
+    use std::path::PathBuf;
+}
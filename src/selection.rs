@@ -0,0 +1,261 @@
+//! Letting the user select several entries at once.
+//!
+//! Rofi only supports selecting multiple entries in dmenu mode.
+//! A [`Selection`] provides the same for plugin modes:
+//! the user toggles entries in and out of the selection with a custom command,
+//! and accepting then acts on the whole selection at once.
+//!
+//! A [`ListMode`] can opt in to multi-selection by setting [`Context::selection`] in its `init` function.
+//! Selected entries are then displayed with [`Style::SELECTED`]
+//! and the number of selected entries is shown in the message bar.
+//! When the user accepts while the selection is not empty,
+//! [`ListMode::select_many`] is called with every selected entry
+//! and the selection is cleared.
+//! Entries are identified by their [identity](crate::list::Entry::identity),
+//! so the selection is kept when the list is sorted or reloaded.
+//!
+//! ```no_run
+//! use rofi_mode::list;
+//! use rofi_mode::selection::Selection;
+//!
+//! struct Mode;
+//!
+//! impl rofi_mode::ListMode<'_> for Mode {
+//!     const NAME: &'static str = "an-example-list-mode\0";
+//!     type Data = ();
+//!
+//!     fn init(cx: &mut list::Context<'_, ()>) -> Result<Self, ()> {
+//!         cx.selection = Some(Selection::new(0));
+//!         cx.entries.extend(["a.txt", "b.txt", "c.txt"].map(list::Entry::from));
+//!         Ok(Self)
+//!     }
+//!
+//!     fn select_many(
+//!         &mut self,
+//!         cx: &mut list::Context<'_, ()>,
+//!         selected: &[usize],
+//!         _alt: bool,
+//!         _input: &mut rofi_mode::String,
+//!     ) -> rofi_mode::Action {
+//!         for &line in selected {
+//!             println!("deleting {}", cx.entries[line].text);
+//!         }
+//!         rofi_mode::Action::Exit
+//!     }
+//!     # fn select(
+//!     #     &mut self,
+//!     #     _cx: &mut list::Context<'_, ()>,
+//!     #     _selected: usize,
+//!     #     _alt: bool,
+//!     #     _input: &mut rofi_mode::String,
+//!     # ) -> rofi_mode::Action {
+//!     #     rofi_mode::Action::Exit
+//!     # }
+//! }
+//! ```
+//!
+//! [`ListMode`]: crate::ListMode
+//! [`ListMode::select_many`]: crate::ListMode::select_many
+//! [`Context::selection`]: crate::list::Context::selection
+
+/// A set of entries selected by the user.
+///
+/// See [the module documentation](self) for more.
+#[derive(Debug, Clone)]
+pub struct Selection {
+    /// The identities of the selected entries, in the order they were selected.
+    selected: Vec<String>,
+    toggle_command: u8,
+    style: Style,
+}
+
+impl Selection {
+    /// Create an empty selection
+    /// whose entries are toggled by the custom command with the given number.
+    #[must_use]
+    pub fn new(toggle_command: u8) -> Self {
+        Self {
+            selected: Vec::new(),
+            toggle_command,
+            style: Style::SELECTED,
+        }
+    }
+
+    /// Set the style selected entries are displayed with,
+    /// [`Style::SELECTED`] by default.
+    #[must_use]
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Get the number of the custom command that toggles the selected entry.
+    #[must_use]
+    pub fn toggle_command(&self) -> u8 {
+        self.toggle_command
+    }
+
+    /// Get the style selected entries are displayed with.
+    #[must_use]
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Get the identities of the selected entries,
+    /// in the order they were selected.
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.selected.iter().map(|id| &**id)
+    }
+
+    /// Get the number of selected entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.selected.len()
+    }
+
+    /// Whether no entries are selected.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    /// Whether the entry with the given identity is selected.
+    #[must_use]
+    pub fn contains(&self, id: &str) -> bool {
+        self.selected.iter().any(|selected| selected == id)
+    }
+
+    /// Add the entry with the given identity to the selection,
+    /// if it isn't selected already.
+    pub fn select(&mut self, id: &str) {
+        if !self.contains(id) {
+            self.selected.push(id.to_owned());
+        }
+    }
+
+    /// Remove the entry with the given identity from the selection.
+    pub fn deselect(&mut self, id: &str) {
+        self.selected.retain(|selected| selected != id);
+    }
+
+    /// Select the entry with the given identity if it isn't selected,
+    /// and deselect it otherwise,
+    /// returning whether it is now selected.
+    pub fn toggle(&mut self, id: &str) -> bool {
+        let selected = !self.contains(id);
+        if selected {
+            self.selected.push(id.to_owned());
+        } else {
+            self.deselect(id);
+        }
+        selected
+    }
+
+    /// Deselect every entry.
+    pub fn clear(&mut self) {
+        self.selected.clear();
+    }
+
+    /// Get the indices of the selected entries in a list,
+    /// in the order they appear in the list.
+    ///
+    /// Selected entries that aren't in the list are skipped.
+    #[must_use]
+    pub fn lines<T>(&self, entries: &[Entry<T>]) -> Vec<usize> {
+        entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| self.contains(entry.identity().as_str()))
+            .map(|(line, _)| line)
+            .collect()
+    }
+}
+
+use crate::list::Entry;
+use crate::Style;
+
+#[cfg(test)]
+mod tests {
+    struct Files;
+
+    impl<'rofi> ListMode<'rofi> for Files {
+        const NAME: &'static str = "files\0";
+        type Data = ();
+
+        fn init(cx: &mut Context<'rofi, ()>) -> Result<Self, ()> {
+            cx.selection = Some(Selection::new(0).with_style(Style::URGENT));
+            cx.entries = vec!["a.txt".into(), "b.txt".into()];
+            Ok(Self)
+        }
+
+        fn select(
+            &mut self,
+            _cx: &mut Context<'rofi, ()>,
+            _selected: usize,
+            _alt: bool,
+            _input: &mut String,
+        ) -> Action {
+            Action::Exit
+        }
+    }
+
+    #[test]
+    fn toggle() {
+        let mut selection = Selection::new(0);
+        assert!(selection.toggle("b"));
+        assert!(selection.toggle("a"));
+        selection.select("b");
+        assert_eq!(selection.iter().collect::<Vec<_>>(), ["b", "a"]);
+        assert!(!selection.toggle("b"));
+        assert!(!selection.contains("b"));
+        assert_eq!(selection.len(), 1);
+        selection.deselect("a");
+        assert!(selection.is_empty());
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn lines() {
+        let mut selection = Selection::new(0);
+        let mut entries: Vec<Entry<()>> = ["a", "b", "c"].map(Entry::from).into();
+        entries[1] = Entry::from("b").with_id("bee");
+        selection.select("c");
+        selection.select("bee");
+        selection.select("d");
+        assert_eq!(selection.lines(&entries), [1, 2]);
+        entries.reverse();
+        assert_eq!(selection.lines(&entries), [0, 1]);
+        entries.swap(0, 2);
+        assert_eq!(selection.lines(&entries), [1, 2]);
+        assert!(selection.lines::<()>(&[]).is_empty());
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn style() {
+        with_list::<Files, _>(|list| {
+            let mut input = String::new();
+            let toggle = Event::CustomCommand {
+                number: 0,
+                selected: Some(1),
+            };
+            assert_eq!(list.entry_style(1), Style::NORMAL);
+            assert_eq!(list.react(toggle, &mut input), Action::Reload);
+            assert_eq!(list.entry_style(0), Style::NORMAL);
+            assert_eq!(list.entry_style(1), Style::URGENT);
+            assert_eq!(list.react(toggle, &mut input), Action::Reload);
+            assert_eq!(list.entry_style(1), Style::NORMAL);
+        });
+    }
+
+    use super::Selection;
+    use crate::list::tests::with_list;
+    use crate::list::Context;
+    use crate::list::Entry;
+    use crate::Action;
+    use crate::Event;
+    use crate::ListMode;
+    use crate::Mode;
+    use crate::String;
+    use crate::Style;
+}
@@ -0,0 +1,311 @@
+//! Quick one-off modes built out of closures.
+//!
+//! For small menus,
+//! defining a type and implementing [`ListMode`] for it can be overkill.
+//! Instead, a [`SimpleMode`] can be built from a handful of closures
+//! and exported directly by passing its builder to [`export_mode!`]:
+//!
+//! ```no_run
+//! use rofi_mode::Action;
+//! use rofi_mode::SimpleMode;
+//!
+//! rofi_mode::export_mode!(
+//!     SimpleMode::builder()
+//!         .name("greet")
+//!         .display_name("Greet")
+//!         .entries(|| ["world", "Rofi"])
+//!         .on_select(|entry| {
+//!             println!("Hello, {}!", entry.text);
+//!             Action::Exit
+//!         })
+//!         .on_custom_command(0, |entry| {
+//!             if let Some(entry) = entry {
+//!                 println!("Goodbye, {}!", entry.text);
+//!             }
+//!             Action::Exit
+//!         })
+//! );
+//! ```
+//!
+//! The builder is run each time the mode is initialized.
+//!
+//! [`export_mode!`]: crate::export_mode
+
+/// A mode built out of closures.
+///
+/// See [the module documentation](self) for more.
+pub struct SimpleMode {
+    display_name: Option<String>,
+    entries: EntriesFn,
+    on_select: SelectFn,
+    custom_commands: Vec<(u8, CustomCommandFn)>,
+}
+
+type EntriesFn = Box<dyn FnMut() -> Vec<Entry<()>> + Sync>;
+type SelectFn = Box<dyn FnMut(&Entry<()>) -> Action + Sync>;
+type CustomCommandFn = Box<dyn FnMut(Option<&Entry<()>>) -> Action + Sync>;
+
+impl SimpleMode {
+    /// Start building a new simple mode,
+    /// beginning with its [name](Unnamed::name).
+    pub fn builder() -> Unnamed {
+        Unnamed { _priv: () }
+    }
+}
+
+impl Debug for SimpleMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimpleMode")
+            .field("display_name", &self.display_name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The start of a builder for a [`SimpleMode`],
+/// created by [`SimpleMode::builder`].
+#[derive(Debug)]
+#[must_use]
+pub struct Unnamed {
+    _priv: (),
+}
+
+impl Unnamed {
+    /// Set the name of the mode.
+    ///
+    /// Its argument must be a string literal,
+    /// since [`export_mode!`](crate::export_mode) reads the name at compile time.
+    /// Unlike [`Mode::NAME`](crate::Mode::NAME),
+    /// the name should not be nul-terminated.
+    pub fn name(self, name: &'static str) -> Builder {
+        Builder {
+            name,
+            mode: SimpleMode {
+                display_name: None,
+                entries: Box::new(Vec::new),
+                on_select: Box::new(|_| Action::Exit),
+                custom_commands: Vec::new(),
+            },
+        }
+    }
+}
+
+/// A builder for a [`SimpleMode`],
+/// created by [`Unnamed::name`].
+#[must_use]
+pub struct Builder {
+    name: &'static str,
+    mode: SimpleMode,
+}
+
+impl Debug for Builder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("name", &self.name)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+impl Builder {
+    /// Set the display name of the mode (the text displayed before the colon).
+    pub fn display_name<S: Into<String>>(mut self, display_name: S) -> Self {
+        self.mode.display_name = Some(display_name.into());
+        self
+    }
+
+    /// Set the function used to produce the list of entries.
+    ///
+    /// It is called when the mode is initialized or reactivated
+    /// and again whenever one of the other callbacks
+    /// returns [`Action::Reload`] or [`Action::Reset`].
+    ///
+    /// By default, the mode has no entries.
+    pub fn entries<F, I>(mut self, mut f: F) -> Self
+    where
+        F: FnMut() -> I + Sync + 'static,
+        I: IntoIterator,
+        I::Item: Into<Entry<()>>,
+    {
+        self.mode.entries = Box::new(move || f().into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the function called when the user accepts an entry from the list.
+    ///
+    /// By default, the mode exits.
+    pub fn on_select<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&Entry<()>) -> Action + Sync + 'static,
+    {
+        self.mode.on_select = Box::new(f);
+        self
+    }
+
+    /// Add a function called when the user runs the custom command with the given number,
+    /// as in [`Event::CustomCommand`].
+    ///
+    /// The function is passed the entry selected at the time, if there is one.
+    /// Custom commands without a handler are ignored.
+    pub fn on_custom_command<F>(mut self, number: u8, f: F) -> Self
+    where
+        F: FnMut(Option<&Entry<()>>) -> Action + Sync + 'static,
+    {
+        self.mode.custom_commands.push((number, Box::new(f)));
+        self
+    }
+}
+
+/// A type that produces the builder of a [`SimpleMode`] exported by [`export_mode!`].
+///
+/// This is an implementation detail of [`export_mode!`] and should not be used directly.
+///
+/// [`export_mode!`]: crate::export_mode
+#[doc(hidden)]
+pub trait Factory: 'static {
+    const NAME: &'static str;
+    fn build() -> Builder;
+}
+
+/// The [`ListMode`] implementation of an exported [`SimpleMode`].
+///
+/// This is an implementation detail of [`export_mode!`] and should not be used directly.
+///
+/// [`export_mode!`]: crate::export_mode
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Exported<F> {
+    mode: SimpleMode,
+    factory: PhantomData<fn() -> F>,
+}
+
+impl<F: Factory> Exported<F> {
+    fn then(&mut self, cx: &mut Context<'_, ()>, action: Action) -> Action {
+        if let Action::Reload | Action::Reset = action {
+            cx.entries = (self.mode.entries)();
+        }
+        action
+    }
+}
+
+impl<'rofi, F: Factory> ListMode<'rofi> for Exported<F> {
+    const NAME: &'static str = F::NAME;
+    type Data = ();
+
+    fn init(cx: &mut Context<'rofi, ()>) -> Result<Self, ()> {
+        let mut mode = F::build().mode;
+        if let Some(display_name) = mode.display_name.take() {
+            cx.api.replace_display_name(display_name);
+        }
+        cx.entries = (mode.entries)();
+        Ok(Self {
+            mode,
+            factory: PhantomData,
+        })
+    }
+
+    fn reactivated(&mut self, cx: &mut Context<'rofi, ()>) {
+        cx.entries = (self.mode.entries)();
+    }
+
+    fn select(
+        &mut self,
+        cx: &mut Context<'rofi, ()>,
+        selected: usize,
+        _alt: bool,
+        _input: &mut String,
+    ) -> Action {
+        let action = (self.mode.on_select)(&cx.entries[selected]);
+        self.then(cx, action)
+    }
+
+    fn react(&mut self, cx: &mut Context<'rofi, ()>, event: Event, input: &mut String) -> Action {
+        let action = match event {
+            Event::Cancel { .. } => return Action::Exit,
+            Event::Complete {
+                selected: Some(selected),
+            } => {
+                *input = cx.entries[selected].completed();
+                return Action::Reload;
+            }
+            Event::CustomCommand { number, selected } => {
+                let handler = self
+                    .mode
+                    .custom_commands
+                    .iter_mut()
+                    .find(|(n, _)| *n == number);
+                match handler {
+                    Some((_, f)) => f(selected.map(|selected| &cx.entries[selected])),
+                    None => return Action::Reload,
+                }
+            }
+            _ => return Action::Reload,
+        };
+        self.then(cx, action)
+    }
+}
+
+use crate::list::Context;
+use crate::list::Entry;
+use crate::Action;
+use crate::Event;
+use crate::ListMode;
+use crate::String;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+#[cfg(test)]
+mod tests {
+    struct Counter;
+
+    impl Factory for Counter {
+        const NAME: &'static str = "counter\0";
+
+        fn build() -> Builder {
+            let mut generation = 0;
+            SimpleMode::builder()
+                .name("counter")
+                .display_name("Counter")
+                .entries(move || {
+                    generation += 1;
+                    [crate::format!("{}", generation), "reload".into()]
+                })
+                .on_select(|entry| match entry.text.as_str() {
+                    "reload" => Action::Reload,
+                    _ => Action::Exit,
+                })
+        }
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn exported() {
+        with_list::<Exported<Counter>, _>(|list| {
+            let mut input = String::new();
+            let select = |selected| Event::Ok {
+                alt: false,
+                selected,
+            };
+            assert_eq!(list.entries(), 2);
+            assert_eq!(list.entry_content(0).as_str(), "1");
+
+            assert_eq!(list.react(select(0), &mut input), Action::Exit);
+            assert_eq!(list.entry_content(0).as_str(), "1");
+
+            assert_eq!(list.react(select(1), &mut input), Action::Reload);
+            assert_eq!(list.entries(), 2);
+            assert_eq!(list.entry_content(0).as_str(), "2");
+        });
+    }
+
+    use super::Builder;
+    use super::Exported;
+    use super::Factory;
+    use crate::list::tests::with_list;
+    use crate::Action;
+    use crate::Event;
+    use crate::Mode;
+    use crate::SimpleMode;
+    use crate::String;
+}
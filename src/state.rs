@@ -0,0 +1,410 @@
+//! Persistent state stored between runs of a mode.
+//!
+//! Each mode gets its own directory under `$XDG_STATE_HOME/rofi/<mode name>/`
+//! (`~/.local/state/rofi/<mode name>/` if `XDG_STATE_HOME` is not set)
+//! in which it can keep typed, serialized [`State`] files.
+//! The mode name used is the one returned by [`Api::mode_name`].
+//!
+//! State is typically loaded in [`Mode::init`]
+//! and saved when the mode is destroyed,
+//! which happens automatically when a modified [`State`] is dropped.
+//! Saving is atomic,
+//! so concurrently running instances of Rofi never observe a partially-written file.
+//!
+//! This module requires the `state` feature.
+//!
+//! ```no_run
+//! use rofi_mode::state::State;
+//! use std::path::PathBuf;
+//!
+//! #[derive(Default, serde::Serialize, serde::Deserialize)]
+//! struct Data {
+//!     last_dir: Option<PathBuf>,
+//! }
+//!
+//! impl rofi_mode::state::Schema for Data {}
+//!
+//! struct Mode<'rofi> {
+//!     api: rofi_mode::Api<'rofi>,
+//!     state: State<Data>,
+//! }
+//!
+//! impl<'rofi> rofi_mode::Mode<'rofi> for Mode<'rofi> {
+//!     const NAME: &'static str = "an-example-mode\0";
+//!
+//!     fn init(api: rofi_mode::Api<'rofi>) -> Result<Self, ()> {
+//!         let state = State::load(&api);
+//!         Ok(Self { api, state })
+//!     }
+//!
+//!     fn destroy(mut self, _action: Option<rofi_mode::Action>) {
+//!         if let Err(e) = self.state.save() {
+//!             eprintln!("failed to save state: {e}");
+//!         }
+//!     }
+//!     # fn entries(&mut self) -> usize { 0 }
+//!     # fn entry_content(&self, _line: usize) -> rofi_mode::String { unreachable!() }
+//!     # fn react(
+//!     #     &mut self,
+//!     #     _event: rofi_mode::Event,
+//!     #     _input: &mut rofi_mode::String,
+//!     # ) -> rofi_mode::Action {
+//!     #     rofi_mode::Action::Exit
+//!     # }
+//! }
+//! ```
+//!
+//! [`Mode::init`]: crate::Mode::init
+
+/// A type that can be stored in a [`State`] file.
+pub trait Schema: Serialize + DeserializeOwned + Default {
+    /// The version of the schema,
+    /// stored in the file alongside the state.
+    ///
+    /// This should be increased whenever the type changes incompatibly,
+    /// and [`Self::migrate`] implemented to convert old state.
+    const VERSION: u32 = 0;
+
+    /// Convert state that was stored with an older version of the schema.
+    ///
+    /// `version` is always less than [`Self::VERSION`].
+    /// Returning [`None`] discards the old state,
+    /// in which case the default value is used instead.
+    ///
+    /// The default implementation always returns [`None`].
+    #[must_use]
+    fn migrate(_version: u32, _data: serde_json::Value) -> Option<Self> {
+        None
+    }
+}
+
+/// A typed state file.
+///
+/// This dereferences to the contained value.
+/// Mutably dereferencing it marks it as modified,
+/// and modified state is saved when it is dropped,
+/// ignoring any errors.
+/// To handle errors, call [`Self::save`] explicitly.
+#[derive(Debug)]
+pub struct State<T: Schema> {
+    path: PathBuf,
+    value: T,
+    modified: bool,
+}
+
+impl<T: Schema> State<T> {
+    /// Load the default state file of the mode, `state.json`.
+    ///
+    /// If the file can't be loaded,
+    /// this falls back to the default value.
+    #[must_use]
+    pub fn load(api: &Api<'_>) -> Self {
+        Self::load_named(api, "state")
+    }
+
+    /// Load a state file of the mode with the given name,
+    /// allowing a mode to store several independent files.
+    ///
+    /// `.json` is appended to the name to get the file name.
+    ///
+    /// If the file can't be loaded,
+    /// this falls back to the default value.
+    /// A file that exists but can't be loaded is renamed with `.bak` appended
+    /// so that saving doesn't overwrite it,
+    /// and if that fails the state is never saved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is empty or contains a path separator or `..`.
+    #[must_use]
+    pub fn load_named(api: &Api<'_>, name: &str) -> Self {
+        assert!(is_valid_name(name), "invalid state file name {name:?}");
+        match mode_dir(api) {
+            Ok(dir) => Self::open_or_set_aside(dir.join(format!("{name}.json"))),
+            Err(_) => Self::new(PathBuf::new()),
+        }
+    }
+
+    /// Load the state file at the given path,
+    /// moving it aside and using the default value if it can't be loaded.
+    fn open_or_set_aside(path: PathBuf) -> Self {
+        if let Ok(state) = Self::open(&path) {
+            return state;
+        }
+        let mut backup = path.clone().into_os_string();
+        backup.push(".bak");
+        match fs::rename(&path, backup) {
+            Ok(()) => Self::new(path),
+            Err(_) => Self::new(PathBuf::new()),
+        }
+    }
+
+    /// Load the state file at the given path.
+    ///
+    /// If the file does not exist, the default value is used.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file could not be read or parsed,
+    /// or if it was stored with a newer version of the schema.
+    pub fn open<P: Into<PathBuf>>(path: P) -> io::Result<Self> {
+        let path = path.into();
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::new(path)),
+            Err(e) => return Err(e),
+        };
+
+        let envelope: Envelope<serde_json::Value> =
+            serde_json::from_slice(&bytes).map_err(invalid_data)?;
+        let value = match envelope.version.cmp(&T::VERSION) {
+            Ordering::Equal => serde_json::from_value(envelope.data).map_err(invalid_data)?,
+            Ordering::Less => T::migrate(envelope.version, envelope.data).unwrap_or_default(),
+            Ordering::Greater => {
+                let message = format!(
+                    "state file has version {}, but the newest supported version is {}",
+                    envelope.version,
+                    T::VERSION,
+                );
+                return Err(invalid_data(message));
+            }
+        };
+
+        Ok(Self {
+            path,
+            value,
+            modified: false,
+        })
+    }
+
+    /// Create state with the default value that is saved to the given path,
+    /// or never saved if the path is empty.
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            value: T::default(),
+            modified: false,
+        }
+    }
+
+    /// Get the path of the state file.
+    ///
+    /// This is empty if the state directory could not be determined,
+    /// in which case the state can't be saved.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Save the state to its file,
+    /// creating the directory if it doesn't exist.
+    ///
+    /// The file is written atomically:
+    /// the state is written to a temporary file which is then moved into place.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the state could not be serialized or written.
+    pub fn save(&mut self) -> io::Result<()> {
+        if self.path.as_os_str().is_empty() {
+            let message = "could not determine the state directory";
+            return Err(io::Error::new(io::ErrorKind::NotFound, message));
+        }
+
+        let envelope = Envelope {
+            version: T::VERSION,
+            data: &self.value,
+        };
+        let bytes = serde_json::to_vec_pretty(&envelope).map_err(invalid_data)?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut temp = self.path.clone().into_os_string();
+        temp.push(format!(".{}.tmp", process::id()));
+        let temp = PathBuf::from(temp);
+
+        let res = (|| {
+            let mut file = File::create(&temp)?;
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+            fs::rename(&temp, &self.path)
+        })();
+        if res.is_err() {
+            _ = fs::remove_file(&temp);
+        }
+        res?;
+
+        self.modified = false;
+        Ok(())
+    }
+}
+
+impl<T: Schema> Deref for State<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: Schema> DerefMut for State<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.modified = true;
+        &mut self.value
+    }
+}
+
+impl<T: Schema> Drop for State<T> {
+    fn drop(&mut self) {
+        if self.modified {
+            _ = self.save();
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u32,
+    data: T,
+}
+
+/// Whether a state file name stays within the mode's state directory.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['/', '\\']) && !name.contains("..")
+}
+
+/// Get the state directory of the current mode.
+fn mode_dir(api: &Api<'_>) -> io::Result<PathBuf> {
+    let base = match env::var_os("XDG_STATE_HOME") {
+        Some(dir) if Path::new(&dir).is_absolute() => PathBuf::from(dir),
+        _ => match env::var_os("HOME") {
+            Some(home) if !home.is_empty() => Path::new(&home).join(".local/state"),
+            _ => return Err(io::Error::new(io::ErrorKind::NotFound, "$HOME is not set")),
+        },
+    };
+    Ok(base.join("rofi").join(&*api.mode_name()))
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+use crate::Api;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Write as _;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+
+#[cfg(test)]
+mod tests {
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct V0 {
+        items: Vec<u32>,
+    }
+
+    impl Schema for V0 {}
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct V1 {
+        items: Vec<u64>,
+    }
+
+    impl Schema for V1 {
+        const VERSION: u32 = 1;
+
+        fn migrate(version: u32, data: serde_json::Value) -> Option<Self> {
+            assert_eq!(version, 0);
+            let old: V0 = serde_json::from_value(data).ok()?;
+            Some(Self {
+                items: old
+                    .items
+                    .into_iter()
+                    .map(|item| u64::from(item) * 2)
+                    .collect(),
+            })
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("rofi-mode-state-test-{}", process::id()));
+        dir.join(name).join("state.json")
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn round_trip() {
+        let path = temp_path("round-trip");
+
+        let mut state = State::<V0>::open(&path).unwrap();
+        assert_eq!(*state, V0::default());
+        state.items.push(5);
+        drop(state);
+
+        let state = State::<V0>::open(&path).unwrap();
+        assert_eq!(state.items, [5]);
+        drop(state);
+
+        let state = State::<V1>::open(&path).unwrap();
+        assert_eq!(state.items, [10]);
+        drop(state);
+
+        let mut state = State::<V1>::open(&path).unwrap();
+        state.save().unwrap();
+        assert!(State::<V0>::open(&path).is_err());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn set_aside() {
+        let path = temp_path("set-aside");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "not json").unwrap();
+
+        let mut state = State::<V0>::open_or_set_aside(path.clone());
+        assert_eq!(state.path(), path);
+        assert_eq!(*state, V0::default());
+        state.items.push(1);
+        drop(state);
+
+        let backup = path.with_extension("json.bak");
+        assert_eq!(fs::read_to_string(backup).unwrap(), "not json");
+        assert_eq!(State::<V0>::open(&path).unwrap().items, [1]);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn names() {
+        assert!(is_valid_name("state"));
+        assert!(is_valid_name("history.v2"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("../state"));
+        assert!(!is_valid_name("a/b"));
+        assert!(!is_valid_name("a\\b"));
+        assert!(!is_valid_name(".."));
+    }
+
+    use super::is_valid_name;
+    use super::Schema;
+    use super::State;
+    use serde::Deserialize;
+    use serde::Serialize;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process;
+}
@@ -0,0 +1,355 @@
+//! Entries laid out in aligned columns.
+//!
+//! A [`Table`] takes rows of plain-text cells
+//! and lays each one out as the text and [`Attributes`] of an entry
+//! so that the columns line up.
+//! Cells are measured with Pango using the font of the current theme,
+//! and the gaps between them are single spaces
+//! stretched with letter spacing to the exact width needed,
+//! so the columns line up even with proportional fonts.
+//!
+//! Each [`Column`] has its own alignment and an optional maximum width,
+//! beyond which its cells are truncated with an ellipsis.
+//! Matching only considers the columns that are [searchable](Column::with_searchable).
+//!
+//! ```
+//! use rofi_mode::list::Entry;
+//! use rofi_mode::table::Alignment;
+//! use rofi_mode::table::Column;
+//! use rofi_mode::table::Table;
+//!
+//! struct Process {
+//!     pid: u32,
+//!     name: String,
+//!     memory: String,
+//! }
+//!
+//! fn entries(processes: Vec<Process>) -> Vec<Entry<u32>> {
+//!     let table = Table::new(vec![
+//!         Column::new().with_alignment(Alignment::Right).with_searchable(false),
+//!         Column::new().with_max_width(300),
+//!         Column::new().with_alignment(Alignment::Right).with_searchable(false),
+//!     ]);
+//!     let rows = processes
+//!         .into_iter()
+//!         .map(|process| {
+//!             let cells = [process.pid.to_string(), process.name, process.memory];
+//!             (cells, process.pid)
+//!         })
+//!         .collect();
+//!     table.entries(rows)
+//! }
+//! ```
+
+/// A layout of entries in aligned columns.
+///
+/// See [the module documentation](self) for more.
+#[derive(Debug, Clone)]
+pub struct Table {
+    columns: Vec<Column>,
+    gap: i32,
+    font: Option<std::string::String>,
+}
+
+impl Table {
+    /// Create a table with the given columns.
+    #[must_use]
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            gap: 16,
+            font: None,
+        }
+    }
+
+    /// Set the gap between columns in pixels.
+    ///
+    /// The default is 16.
+    #[must_use]
+    pub fn with_gap(mut self, gap: u16) -> Self {
+        self.gap = i32::from(gap);
+        self
+    }
+
+    /// Set the font the cells are measured with,
+    /// as a Pango font description like `Sans 12`.
+    ///
+    /// By default the font of the current theme's `element-text` is used.
+    #[must_use]
+    pub fn with_font<F: Into<std::string::String>>(mut self, font: F) -> Self {
+        self.font = Some(font.into());
+        self
+    }
+
+    /// Get the columns of the table.
+    #[must_use]
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Lay out rows of cells,
+    /// for use in [`Mode::entry_content`] and [`Mode::entry_attributes`].
+    ///
+    /// Cells beyond the number of columns are ignored,
+    /// and missing cells are left empty.
+    ///
+    /// [`Mode::entry_content`]: crate::Mode::entry_content
+    /// [`Mode::entry_attributes`]: crate::Mode::entry_attributes
+    #[must_use]
+    pub fn layout<R, S>(&self, rows: &[R]) -> Vec<Row>
+    where
+        R: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        let measure = Measure::new(self.font.as_deref());
+        self.layout_with(rows, |text| measure.width(text))
+    }
+
+    /// Lay out rows of data as list entries,
+    /// each made from the cells in the first element of its tuple.
+    ///
+    /// See [`Self::layout`] for more.
+    #[must_use]
+    pub fn entries<T, R, S>(&self, rows: Vec<(R, T)>) -> Vec<Entry<T>>
+    where
+        R: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        let (cells, data): (Vec<R>, Vec<T>) = rows.into_iter().unzip();
+        self.layout(&cells)
+            .into_iter()
+            .zip(data)
+            .map(|(row, data)| {
+                Entry::new(row.text, data)
+                    .with_attributes(row.attributes)
+                    .with_search(row.search)
+            })
+            .collect()
+    }
+
+    fn layout_with<R, S, M>(&self, rows: &[R], mut measure: M) -> Vec<Row>
+    where
+        R: AsRef<[S]>,
+        S: AsRef<str>,
+        M: FnMut(&str) -> i32,
+    {
+        let cells: Vec<Vec<(std::string::String, i32)>> = rows
+            .iter()
+            .map(|row| {
+                let row = row.as_ref();
+                self.columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, column)| {
+                        let text = row.get(i).map_or("", AsRef::as_ref);
+                        truncate(text, column.max_width, &mut measure)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let widths: Vec<i32> = (0..self.columns.len())
+            .map(|i| cells.iter().map(|row| row[i].1).max().unwrap_or(0))
+            .collect();
+        let space = measure(" ");
+
+        rows.iter()
+            .zip(cells)
+            .map(|(row, cells)| {
+                let mut text = String::new();
+                let mut attributes = Attributes::new();
+                let mut pending = 0;
+                for (i, (column, (cell, width))) in self.columns.iter().zip(cells).enumerate() {
+                    let slack = widths[i] - width;
+                    let (before, after) = match column.alignment {
+                        Alignment::Left => (0, slack),
+                        Alignment::Right => (slack, 0),
+                        Alignment::Center => (slack / 2, slack - slack / 2),
+                    };
+                    pending += before;
+                    if !cell.is_empty() {
+                        if pending != 0 {
+                            let start = text.len();
+                            text.push(' ');
+                            let spacing = (pending - space) * pango::SCALE;
+                            let attribute = pango::AttrInt::new_letter_spacing(spacing);
+                            attributes.push_range(start..text.len(), attribute);
+                            pending = 0;
+                        }
+                        text.push_str(&cell);
+                    }
+                    pending += after;
+                    if i + 1 < self.columns.len() {
+                        pending += self.gap;
+                    }
+                }
+
+                let row = row.as_ref();
+                let mut search = String::new();
+                for (i, column) in self.columns.iter().enumerate() {
+                    let cell = row.get(i).map_or("", AsRef::as_ref);
+                    if column.searchable && !cell.is_empty() {
+                        if !search.is_empty() {
+                            search.push(' ');
+                        }
+                        search.push_str(cell);
+                    }
+                }
+
+                Row {
+                    text,
+                    attributes,
+                    search,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A column of a [`Table`].
+#[derive(Debug, Clone)]
+pub struct Column {
+    alignment: Alignment,
+    max_width: Option<i32>,
+    searchable: bool,
+}
+
+impl Column {
+    /// Create a left-aligned, searchable column with no maximum width.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            alignment: Alignment::Left,
+            max_width: None,
+            searchable: true,
+        }
+    }
+
+    /// Set how the cells are aligned within the column.
+    #[must_use]
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Set the maximum width of the column in pixels.
+    ///
+    /// Cells wider than this are truncated with an ellipsis.
+    #[must_use]
+    pub fn with_max_width(mut self, max_width: u16) -> Self {
+        self.max_width = Some(i32::from(max_width));
+        self
+    }
+
+    /// Set whether the user's input is matched against the column.
+    ///
+    /// Truncated cells are matched against in full.
+    #[must_use]
+    pub fn with_searchable(mut self, searchable: bool) -> Self {
+        self.searchable = searchable;
+        self
+    }
+
+    /// Get how the cells are aligned within the column.
+    #[must_use]
+    pub fn alignment(&self) -> Alignment {
+        self.alignment
+    }
+
+    /// Whether the user's input is matched against the column.
+    #[must_use]
+    pub fn is_searchable(&self) -> bool {
+        self.searchable
+    }
+}
+
+impl Default for Column {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How the cells of a [`Column`] are aligned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Alignment {
+    /// Align cells to the left of the column.
+    Left,
+    /// Align cells to the right of the column.
+    Right,
+    /// Center cells in the column.
+    Center,
+}
+
+/// A row of a [`Table`] laid out as the content of an entry.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Row {
+    /// The text of the entry.
+    pub text: String,
+    /// The attributes aligning the columns of the text.
+    pub attributes: Attributes,
+    /// The text of the searchable columns, separated by spaces,
+    /// to match the user's input against.
+    pub search: String,
+}
+
+/// Truncate text to fit within a maximum width, adding an ellipsis,
+/// and return it along with its width.
+fn truncate<M: FnMut(&str) -> i32>(
+    text: &str,
+    max_width: Option<i32>,
+    measure: &mut M,
+) -> (std::string::String, i32) {
+    let truncated = max_width
+        .and_then(|max_width| ellipsize_with(text, Position::End, max_width, &mut *measure));
+    let text = truncated.unwrap_or_else(|| text.to_owned());
+    let width = measure(&text);
+    (text, width)
+}
+
+use crate::ellipsize::ellipsize_with;
+use crate::ellipsize::Position;
+use crate::list::Entry;
+use crate::measure::Measure;
+use crate::Attributes;
+use crate::String;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(not(miri))]
+    fn columns() {
+        let table = Table::new(vec![
+            Column::new().with_alignment(Alignment::Right),
+            Column::new().with_max_width(40).with_searchable(false),
+            Column::new(),
+        ])
+        .with_gap(5);
+        let rows = [vec!["1", "abc", "x"], vec!["100", "abcdefgh"]];
+        let rows = table.layout_with(&rows, |text| 10 * text.chars().count() as i32);
+
+        let spacings = |row: &Row| -> Vec<_> {
+            row.attributes
+                .iter()
+                .map(|attribute| {
+                    let spacing = attribute.downcast_ref::<pango::AttrInt>().unwrap().value();
+                    (attribute.start_index(), spacing / pango::SCALE)
+                })
+                .collect()
+        };
+
+        assert_eq!(rows[0].text.as_str(), " 1 abc x");
+        assert_eq!(spacings(&rows[0]), [(0, 10), (2, -5), (6, 5)]);
+        assert_eq!(rows[0].search.as_str(), "1 x");
+
+        assert_eq!(rows[1].text.as_str(), "100 abc…");
+        assert_eq!(spacings(&rows[1]), [(3, -5)]);
+        assert_eq!(rows[1].search.as_str(), "100");
+    }
+
+    use super::Alignment;
+    use super::Column;
+    use super::Row;
+    use super::Table;
+}
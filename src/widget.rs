@@ -0,0 +1,651 @@
+//! Settings widgets built from list entries.
+//!
+//! A [`Panel`] is a list of widgets that manage their own state:
+//! [`Toggle`]s that are switched on and off,
+//! [`Radio`] groups of which exactly one option is chosen,
+//! and [`Slider`]s stepping through a range of numbers.
+//! The panel renders its widgets into the entries of a [`ListMode`]
+//! and updates them in response to the user's input:
+//! accepting a toggle switches it,
+//! accepting a radio option chooses it,
+//! and accepting a slider increases it (or decreases it with the alt binding).
+//! Sliders can also be adjusted with two [custom commands](Panel::with_adjust_commands).
+//!
+//! Toggles that are on and chosen radio options are displayed with [`Style::ACTIVE`].
+//! Each widget can have a callback that is called whenever the user changes it;
+//! if the callback returns an error,
+//! the change is undone,
+//! the widget is displayed with [`Style::URGENT`]
+//! and the error is available from [`Panel::error`].
+//!
+//! ```no_run
+//! use rofi_mode::list;
+//! use rofi_mode::markup::Markup;
+//! use rofi_mode::widget::{Line, Panel, Radio, Slider, Toggle};
+//!
+//! struct Mode {
+//!     panel: Panel,
+//! }
+//!
+//! impl rofi_mode::ListMode<'_> for Mode {
+//!     const NAME: &'static str = "settings\0";
+//!     type Data = Line;
+//!
+//!     fn init(cx: &mut list::Context<'_, Line>) -> Result<Self, ()> {
+//!         let panel = Panel::new()
+//!             .with(Toggle::new("VPN", false).with_callback(|on| {
+//!                 println!("VPN {}", if on { "on" } else { "off" });
+//!                 Ok(())
+//!             }))
+//!             .with(Radio::new("Output", ["Speakers", "Headphones"], 0))
+//!             .with(Slider::new("Brightness", 0, 100).with_step(10))
+//!             .with_adjust_commands(0, 1);
+//!         panel.render(cx);
+//!         Ok(Self { panel })
+//!     }
+//!
+//!     fn select(
+//!         &mut self,
+//!         cx: &mut list::Context<'_, Line>,
+//!         selected: usize,
+//!         alt: bool,
+//!         _input: &mut rofi_mode::String,
+//!     ) -> rofi_mode::Action {
+//!         self.panel.activate(cx, selected, alt);
+//!         rofi_mode::Action::Reload
+//!     }
+//!
+//!     fn react(
+//!         &mut self,
+//!         cx: &mut list::Context<'_, Line>,
+//!         event: rofi_mode::Event,
+//!         _input: &mut rofi_mode::String,
+//!     ) -> rofi_mode::Action {
+//!         if self.panel.react(cx, event) {
+//!             return rofi_mode::Action::Reload;
+//!         }
+//!         match event {
+//!             rofi_mode::Event::Cancel { .. } => rofi_mode::Action::Exit,
+//!             _ => rofi_mode::Action::Reload,
+//!         }
+//!     }
+//!
+//!     fn message(&mut self, _cx: &mut list::Context<'_, Line>) -> rofi_mode::String {
+//!         match self.panel.error() {
+//!             Some(error) => Markup::from(error).into_string(),
+//!             None => rofi_mode::String::new(),
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`ListMode`]: crate::ListMode
+
+/// A list of widgets displayed as the entries of a [`ListMode`](crate::ListMode).
+///
+/// See [the module documentation](self) for more.
+#[derive(Debug, Default)]
+pub struct Panel {
+    widgets: Vec<Widget>,
+    adjust_commands: Option<(u8, u8)>,
+    error: Option<String>,
+}
+
+impl Panel {
+    /// Create an empty panel.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a widget to the end of the panel.
+    #[must_use]
+    pub fn with<W: Into<Widget>>(mut self, widget: W) -> Self {
+        self.widgets.push(widget.into());
+        self
+    }
+
+    /// Set the numbers of the custom commands
+    /// that decrease and increase the selected slider respectively.
+    #[must_use]
+    pub fn with_adjust_commands(mut self, decrease: u8, increase: u8) -> Self {
+        self.adjust_commands = Some((decrease, increase));
+        self
+    }
+
+    /// Get the widgets of the panel.
+    #[must_use]
+    pub fn widgets(&self) -> &[Widget] {
+        &self.widgets
+    }
+
+    /// Get the widgets of the panel mutably.
+    ///
+    /// Call [`Self::render`] after changing them to update the list.
+    pub fn widgets_mut(&mut self) -> &mut Vec<Widget> {
+        &mut self.widgets
+    }
+
+    /// Get the error returned by the callback of the last change,
+    /// if it failed.
+    ///
+    /// The error is plain text,
+    /// so it must be escaped to be shown in the message bar.
+    #[must_use]
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+
+    /// Replace the entries of the list with the panel's widgets.
+    pub fn render(&self, cx: &mut Context<'_, Line>) {
+        cx.entries.clear();
+        for (widget_index, widget) in self.widgets.iter().enumerate() {
+            for option in 0..widget.lines() {
+                let line = Line {
+                    widget: widget_index,
+                    option,
+                };
+                // The text changes with the widget's state,
+                // so identify the entry by its position instead.
+                let entry = Entry::new(widget.text(option), line)
+                    .with_id(crate::format!("{widget_index}/{option}"))
+                    .with_style(widget.style(option))
+                    .with_meta(widget.label().clone());
+                cx.entries.push(entry);
+            }
+        }
+    }
+
+    /// Activate the widget on the given line of the list,
+    /// as when the user accepts it,
+    /// and render the panel again.
+    pub fn activate(&mut self, cx: &mut Context<'_, Line>, line: usize, alt: bool) {
+        let Line { widget, option } = cx.entries[line].data;
+        let res = match &mut self.widgets[widget] {
+            Widget::Toggle(toggle) => toggle.change(!toggle.on),
+            Widget::Radio(radio) => radio.change(option),
+            Widget::Slider(slider) if alt => slider.decrease(),
+            Widget::Slider(slider) => slider.increase(),
+        };
+        self.error = res.err();
+        self.render(cx);
+    }
+
+    /// Handle an event if it is one of the adjust commands used on a slider,
+    /// returning whether it was handled.
+    pub fn react(&mut self, cx: &mut Context<'_, Line>, event: Event) -> bool {
+        let (decrease, increase) = match self.adjust_commands {
+            Some(commands) => commands,
+            None => return false,
+        };
+        let (number, line) = match event {
+            Event::CustomCommand {
+                number,
+                selected: Some(line),
+            } if number == decrease || number == increase => (number, line),
+            _ => return false,
+        };
+        let slider = match &mut self.widgets[cx.entries[line].data.widget] {
+            Widget::Slider(slider) => slider,
+            _ => return false,
+        };
+        let res = if number == decrease {
+            slider.decrease()
+        } else {
+            slider.increase()
+        };
+        self.error = res.err();
+        self.render(cx);
+        true
+    }
+}
+
+/// The data of an entry rendered by a [`Panel`],
+/// identifying which widget it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line {
+    widget: usize,
+    option: usize,
+}
+
+impl Line {
+    /// Get the index of the widget in the panel.
+    #[must_use]
+    pub fn widget(self) -> usize {
+        self.widget
+    }
+
+    /// Get the index of the option in the widget,
+    /// which is always zero for widgets other than radio groups.
+    #[must_use]
+    pub fn option(self) -> usize {
+        self.option
+    }
+}
+
+/// A widget in a [`Panel`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Widget {
+    /// A switch that is either on or off.
+    Toggle(Toggle),
+    /// A group of options of which exactly one is chosen.
+    Radio(Radio),
+    /// A number in a range.
+    Slider(Slider),
+}
+
+impl Widget {
+    /// Get the label of the widget.
+    #[must_use]
+    pub fn label(&self) -> &String {
+        match self {
+            Self::Toggle(toggle) => &toggle.label,
+            Self::Radio(radio) => &radio.label,
+            Self::Slider(slider) => &slider.label,
+        }
+    }
+
+    /// Whether the last change to the widget failed.
+    #[must_use]
+    pub fn is_urgent(&self) -> bool {
+        match self {
+            Self::Toggle(toggle) => toggle.urgent,
+            Self::Radio(radio) => radio.urgent,
+            Self::Slider(slider) => slider.urgent,
+        }
+    }
+
+    /// The number of lines the widget takes up in the list.
+    fn lines(&self) -> usize {
+        match self {
+            Self::Radio(radio) => radio.options.len(),
+            Self::Toggle(_) | Self::Slider(_) => 1,
+        }
+    }
+
+    fn text(&self, option: usize) -> String {
+        match self {
+            Self::Toggle(toggle) => {
+                let check = if toggle.on { 'x' } else { ' ' };
+                crate::format!("[{check}] {}", toggle.label)
+            }
+            Self::Radio(radio) => {
+                let dot = if radio.selected == option { '*' } else { ' ' };
+                crate::format!("({dot}) {}", radio.options[option])
+            }
+            Self::Slider(slider) => {
+                crate::format!("{} [{}] {}", slider.label, slider.bar(), slider.value)
+            }
+        }
+    }
+
+    fn style(&self, option: usize) -> Style {
+        let active = match self {
+            Self::Toggle(toggle) => toggle.on,
+            Self::Radio(radio) => radio.selected == option,
+            Self::Slider(_) => false,
+        };
+        let mut style = Style::NORMAL;
+        if active {
+            style |= Style::ACTIVE;
+        }
+        if self.is_urgent() {
+            style |= Style::URGENT;
+        }
+        style
+    }
+}
+
+impl From<Toggle> for Widget {
+    fn from(toggle: Toggle) -> Self {
+        Self::Toggle(toggle)
+    }
+}
+
+impl From<Radio> for Widget {
+    fn from(radio: Radio) -> Self {
+        Self::Radio(radio)
+    }
+}
+
+impl From<Slider> for Widget {
+    fn from(slider: Slider) -> Self {
+        Self::Slider(slider)
+    }
+}
+
+/// A callback called with the new value of a widget when the user changes it.
+type Callback<T> = Box<dyn FnMut(T) -> Result<(), String> + Sync>;
+
+/// Change the value of a widget,
+/// calling its callback and marking it as urgent if the callback fails.
+fn change<T: Copy>(
+    value: &mut T,
+    new: T,
+    urgent: &mut bool,
+    callback: &mut Option<Callback<T>>,
+) -> Result<(), String> {
+    if let Some(callback) = callback {
+        if let Err(e) = callback(new) {
+            *urgent = true;
+            return Err(e);
+        }
+    }
+    *value = new;
+    *urgent = false;
+    Ok(())
+}
+
+/// A switch that is either on or off.
+pub struct Toggle {
+    label: String,
+    on: bool,
+    urgent: bool,
+    callback: Option<Callback<bool>>,
+}
+
+impl Toggle {
+    /// Create a toggle with the given label and initial state.
+    #[must_use]
+    pub fn new<L: Into<String>>(label: L, on: bool) -> Self {
+        Self {
+            label: label.into(),
+            on,
+            urgent: false,
+            callback: None,
+        }
+    }
+
+    /// Set the callback called with the new state whenever the user switches the toggle.
+    ///
+    /// If it returns an error, the toggle is not switched.
+    #[must_use]
+    pub fn with_callback<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(bool) -> Result<(), String> + Sync + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Whether the toggle is on.
+    #[must_use]
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Switch the toggle on or off without calling its callback.
+    pub fn set(&mut self, on: bool) {
+        self.on = on;
+        self.urgent = false;
+    }
+
+    fn change(&mut self, on: bool) -> Result<(), String> {
+        change(&mut self.on, on, &mut self.urgent, &mut self.callback)
+    }
+}
+
+impl Debug for Toggle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Toggle")
+            .field("label", &self.label)
+            .field("on", &self.on)
+            .field("urgent", &self.urgent)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A group of options of which exactly one is chosen,
+/// each displayed on its own line.
+pub struct Radio {
+    label: String,
+    options: Vec<String>,
+    selected: usize,
+    urgent: bool,
+    callback: Option<Callback<usize>>,
+}
+
+impl Radio {
+    /// Create a radio group with the given label, options and initially chosen option.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `selected` is not the index of an option.
+    #[must_use]
+    pub fn new<L, I>(label: L, options: I, selected: usize) -> Self
+    where
+        L: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let options: Vec<String> = options.into_iter().map(Into::into).collect();
+        assert!(selected < options.len(), "selected option out of bounds");
+        Self {
+            label: label.into(),
+            options,
+            selected,
+            urgent: false,
+            callback: None,
+        }
+    }
+
+    /// Set the callback called with the index of the newly chosen option
+    /// whenever the user chooses a different one.
+    ///
+    /// If it returns an error, the previous option stays chosen.
+    #[must_use]
+    pub fn with_callback<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(usize) -> Result<(), String> + Sync + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Get the options of the group.
+    #[must_use]
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    /// Get the index of the chosen option.
+    #[must_use]
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Choose an option without calling the callback.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `selected` is not the index of an option.
+    pub fn set(&mut self, selected: usize) {
+        assert!(
+            selected < self.options.len(),
+            "selected option out of bounds"
+        );
+        self.selected = selected;
+        self.urgent = false;
+    }
+
+    fn change(&mut self, selected: usize) -> Result<(), String> {
+        if selected == self.selected {
+            return Ok(());
+        }
+        change(
+            &mut self.selected,
+            selected,
+            &mut self.urgent,
+            &mut self.callback,
+        )
+    }
+}
+
+impl Debug for Radio {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Radio")
+            .field("label", &self.label)
+            .field("options", &self.options)
+            .field("selected", &self.selected)
+            .field("urgent", &self.urgent)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A number in a range, adjusted in fixed steps.
+pub struct Slider {
+    label: String,
+    value: i64,
+    min: i64,
+    max: i64,
+    step: i64,
+    urgent: bool,
+    callback: Option<Callback<i64>>,
+}
+
+impl Slider {
+    /// Create a slider with the given label and inclusive range,
+    /// starting at the minimum with a step of one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is greater than `max`.
+    #[must_use]
+    pub fn new<L: Into<String>>(label: L, min: i64, max: i64) -> Self {
+        assert!(min <= max, "slider minimum is greater than its maximum");
+        Self {
+            label: label.into(),
+            value: min,
+            min,
+            max,
+            step: 1,
+            urgent: false,
+            callback: None,
+        }
+    }
+
+    /// Set the initial value of the slider,
+    /// clamped to its range.
+    #[must_use]
+    pub fn with_value(mut self, value: i64) -> Self {
+        self.set(value);
+        self
+    }
+
+    /// Set the amount the slider changes by in each step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is not positive.
+    #[must_use]
+    pub fn with_step(mut self, step: i64) -> Self {
+        assert!(step > 0, "slider step must be positive");
+        self.step = step;
+        self
+    }
+
+    /// Set the callback called with the new value whenever the user adjusts the slider.
+    ///
+    /// If it returns an error, the slider keeps its previous value.
+    #[must_use]
+    pub fn with_callback<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(i64) -> Result<(), String> + Sync + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Get the value of the slider.
+    #[must_use]
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+
+    /// Set the value of the slider without calling its callback,
+    /// clamped to its range.
+    pub fn set(&mut self, value: i64) {
+        self.value = value.clamp(self.min, self.max);
+        self.urgent = false;
+    }
+
+    fn increase(&mut self) -> Result<(), String> {
+        self.adjust(self.value.saturating_add(self.step))
+    }
+
+    fn decrease(&mut self) -> Result<(), String> {
+        self.adjust(self.value.saturating_sub(self.step))
+    }
+
+    fn adjust(&mut self, value: i64) -> Result<(), String> {
+        let value = value.clamp(self.min, self.max);
+        if value == self.value {
+            return Ok(());
+        }
+        change(&mut self.value, value, &mut self.urgent, &mut self.callback)
+    }
+
+    /// Draw the position of the slider in its range as a bar.
+    fn bar(&self) -> String {
+        const WIDTH: i128 = 10;
+        let range = i128::from(self.max) - i128::from(self.min);
+        let filled = if range == 0 {
+            WIDTH
+        } else {
+            (i128::from(self.value) - i128::from(self.min)) * WIDTH / range
+        };
+        (0..WIDTH)
+            .map(|i| if i < filled { '#' } else { '-' })
+            .collect()
+    }
+}
+
+impl Debug for Slider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Slider")
+            .field("label", &self.label)
+            .field("value", &self.value)
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .field("step", &self.step)
+            .field("urgent", &self.urgent)
+            .finish_non_exhaustive()
+    }
+}
+
+use crate::list::Context;
+use crate::list::Entry;
+use crate::Event;
+use crate::String;
+use crate::Style;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn slider() {
+        let mut slider = Slider::new("", 0, 25).with_step(10).with_value(20);
+        assert_eq!(slider.bar().as_str(), "########--");
+        slider.increase().unwrap();
+        assert_eq!(slider.value(), 25);
+        assert_eq!(slider.bar().as_str(), "##########");
+
+        let mut slider = slider.with_callback(|value| {
+            if value < 10 {
+                return Err("too low".into());
+            }
+            Ok(())
+        });
+        slider.decrease().unwrap();
+        assert_eq!(slider.value(), 15);
+        assert_eq!(slider.decrease().unwrap_err().as_str(), "too low");
+        assert_eq!(slider.value(), 15);
+        assert!(Widget::from(slider).is_urgent());
+    }
+
+    use super::Slider;
+    use super::Widget;
+}
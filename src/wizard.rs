@@ -0,0 +1,319 @@
+//! Collecting several values from the user, one prompt at a time.
+//!
+//! A [`Wizard`] is a sequence of [`Step`]s,
+//! each of which prompts the user for a value
+//! and stores it in a typed result.
+//! It is run inside a [`ListMode`] with [`Context::run_wizard`].
+//! While it is running,
+//! the list shows the current step's suggestions,
+//! the display name is set to the step's prompt
+//! and validation errors are shown in the message bar.
+//!
+//! The user answers a step by selecting a suggestion ([`Event::Ok`])
+//! or by entering their own input ([`Event::CustomInput`]).
+//! Cancelling returns to the previous step,
+//! restoring the answer that was given to it.
+//! Once the last step has been answered,
+//! the callback passed to [`Context::run_wizard`] is called with the result;
+//! cancelling the first step aborts the wizard
+//! and calls it with [`None`] instead.
+//!
+//! ```
+//! use rofi_mode::list;
+//! use rofi_mode::wizard::Step;
+//! use rofi_mode::wizard::Wizard;
+//! use std::path::PathBuf;
+//!
+//! #[derive(Default)]
+//! struct Bookmark {
+//!     name: String,
+//!     path: PathBuf,
+//!     tags: Vec<String>,
+//! }
+//!
+//! fn new_bookmark(cx: &mut list::Context<'_, PathBuf>) -> rofi_mode::Action {
+//!     let wizard = Wizard::new()
+//!         .step(Step::new("name", |bookmark: &mut Bookmark, input| {
+//!             if input.is_empty() {
+//!                 return Err("the name can't be empty".into());
+//!             }
+//!             bookmark.name = input.to_owned();
+//!             Ok(())
+//!         }))
+//!         .step(
+//!             Step::new("path", |bookmark: &mut Bookmark, input| {
+//!                 bookmark.path = PathBuf::from(input);
+//!                 Ok(())
+//!             })
+//!             .with_suggestions(["/home", "/tmp"]),
+//!         )
+//!         .step(
+//!             Step::new("tags", |bookmark: &mut Bookmark, input| {
+//!                 bookmark.tags = input.split_whitespace().map(str::to_owned).collect();
+//!                 Ok(())
+//!             })
+//!             .with_message("Separate tags with spaces"),
+//!         );
+//!
+//!     cx.run_wizard(wizard, |cx, bookmark| {
+//!         if let Some(bookmark) = bookmark {
+//!             cx.entries.push(list::Entry::new(bookmark.name, bookmark.path));
+//!         }
+//!         rofi_mode::Action::Reload
+//!     });
+//!     rofi_mode::Action::Reload
+//! }
+//! ```
+//!
+//! [`ListMode`]: crate::ListMode
+//! [`Context::run_wizard`]: crate::list::Context::run_wizard
+//! [`Event::Ok`]: crate::Event::Ok
+//! [`Event::CustomInput`]: crate::Event::CustomInput
+
+/// A sequence of prompts building up a value of type `S`.
+///
+/// See [the module documentation](self) for more.
+pub struct Wizard<S> {
+    steps: Vec<Step<S>>,
+    value: S,
+    current: usize,
+    /// The answers given to the previous steps.
+    answers: Vec<String>,
+    error: Option<Markup>,
+}
+
+impl<S: Default> Wizard<S> {
+    /// Create a wizard with no steps,
+    /// starting from the default value of `S`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_value(S::default())
+    }
+}
+
+impl<S: Default> Default for Wizard<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Wizard<S> {
+    /// Create a wizard with no steps,
+    /// starting from the given value.
+    #[must_use]
+    pub fn with_value(value: S) -> Self {
+        Self {
+            steps: Vec::new(),
+            value,
+            current: 0,
+            answers: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Add a step to the end of the wizard.
+    #[must_use]
+    pub fn step(mut self, step: Step<S>) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Get the number of steps in the wizard.
+    #[must_use]
+    pub fn steps(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Get the index of the step currently being answered.
+    #[must_use]
+    pub fn current_step(&self) -> usize {
+        self.current
+    }
+
+    /// Get the value built up by the steps answered so far.
+    #[must_use]
+    pub fn value(&self) -> &S {
+        &self.value
+    }
+
+    /// Get the prompt of the current step.
+    pub(crate) fn prompt(&self) -> &str {
+        &self.steps[self.current].prompt
+    }
+
+    /// Get the message to display for the current step:
+    /// the last validation error if there was one,
+    /// and the step's message otherwise.
+    pub(crate) fn message(&self) -> Option<&Markup> {
+        self.error
+            .as_ref()
+            .or(self.steps[self.current].message.as_ref())
+    }
+
+    /// Get the suggestions of the current step.
+    pub(crate) fn suggestions(&self) -> &[String] {
+        &self.steps[self.current].suggestions
+    }
+
+    /// Answer the current step.
+    pub(crate) fn answer(&mut self, input: &str) -> Progress {
+        let step = &mut self.steps[self.current];
+        if let Err(error) = (step.apply)(&mut self.value, input) {
+            self.error = Some(error.into());
+            return Progress::Invalid;
+        }
+        self.error = None;
+
+        self.answers.truncate(self.current);
+        self.answers.push(input.into());
+        self.current += 1;
+        if self.current == self.steps.len() {
+            return Progress::Finished;
+        }
+        Progress::Next
+    }
+
+    /// Return to the previous step,
+    /// returning the answer that was given to it,
+    /// or [`None`] if this is the first step.
+    pub(crate) fn back(&mut self) -> Option<String> {
+        self.error = None;
+        self.current = self.current.checked_sub(1)?;
+        Some(self.answers[self.current].clone())
+    }
+
+    /// Get the value built up by the wizard.
+    pub(crate) fn into_value(self) -> S {
+        self.value
+    }
+}
+
+impl<S: Debug> Debug for Wizard<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Wizard")
+            .field("steps", &self.steps)
+            .field("value", &self.value)
+            .field("current", &self.current)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The result of answering a step of a [`Wizard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Progress {
+    /// The answer was invalid, and the step must be answered again.
+    Invalid,
+    /// The answer was accepted, and the wizard moved to the next step.
+    Next,
+    /// The answer was accepted, and it was the last step.
+    Finished,
+}
+
+/// A single prompt in a [`Wizard`].
+pub struct Step<S> {
+    prompt: String,
+    message: Option<Markup>,
+    suggestions: Vec<String>,
+    apply: ApplyFn<S>,
+}
+
+type ApplyFn<S> = Box<dyn FnMut(&mut S, &str) -> Result<(), String> + Sync>;
+
+impl<S> Step<S> {
+    /// Create a step with the given prompt,
+    /// which is displayed as the display name of the mode.
+    ///
+    /// `apply` is called with the user's answer.
+    /// It should validate the answer and store it in the value,
+    /// or return an error message to show to the user
+    /// so they can try again.
+    /// The error message is escaped before it is shown.
+    #[must_use]
+    pub fn new<P, F>(prompt: P, apply: F) -> Self
+    where
+        P: Into<String>,
+        F: FnMut(&mut S, &str) -> Result<(), String> + Sync + 'static,
+    {
+        Self {
+            prompt: prompt.into(),
+            message: None,
+            suggestions: Vec::new(),
+            apply: Box::new(apply),
+        }
+    }
+
+    /// Set a message to show in the message bar during this step.
+    ///
+    /// The message is escaped unless it is [`Markup`].
+    #[must_use]
+    pub fn with_message<M: Into<Markup>>(mut self, message: M) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Set the suggestions listed during this step,
+    /// any of which the user can select as their answer.
+    #[must_use]
+    pub fn with_suggestions<I>(mut self, suggestions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.suggestions = suggestions.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl<S> Debug for Step<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Step")
+            .field("prompt", &self.prompt)
+            .field("message", &self.message)
+            .field("suggestions", &self.suggestions)
+            .finish_non_exhaustive()
+    }
+}
+
+use crate::markup::Markup;
+use crate::String;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn steps() {
+        let mut wizard = Wizard::<(u32, u32)>::new()
+            .step(Step::new("a", |value: &mut (u32, u32), input| {
+                value.0 = input
+                    .parse()
+                    .map_err(|_| String::from("not a number < 1"))?;
+                Ok(())
+            }))
+            .step(Step::new("b", |value: &mut (u32, u32), input| {
+                value.1 = input.parse().map_err(|_| String::from("not a number"))?;
+                Ok(())
+            }));
+
+        assert_eq!(wizard.prompt(), "a");
+        assert_eq!(wizard.back(), None);
+
+        assert_eq!(wizard.answer("x"), Progress::Invalid);
+        assert_eq!(wizard.message().unwrap().as_str(), "not a number &lt; 1");
+        assert_eq!(wizard.answer("1"), Progress::Next);
+        assert_eq!(wizard.message(), None);
+        assert_eq!(wizard.prompt(), "b");
+
+        assert_eq!(wizard.back().unwrap().as_str(), "1");
+        assert_eq!(wizard.prompt(), "a");
+        assert_eq!(wizard.answer("2"), Progress::Next);
+        assert_eq!(wizard.answer("3"), Progress::Finished);
+        assert_eq!(wizard.into_value(), (2, 3));
+    }
+
+    use super::Progress;
+    use super::Step;
+    use super::Wizard;
+    use crate::String;
+}